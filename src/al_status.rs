@@ -0,0 +1,142 @@
+// Part of ethercat-rs. Copyright 2018-2022 by the authors.
+// This work is dual-licensed under Apache 2.0 and MIT terms.
+
+//! AL status codes (register 0x0134) as defined by ETG.1000.6.
+
+use std::fmt;
+
+macro_rules! al_status_codes {
+    ($($name:ident = $code:literal => $descr:literal,)*) => {
+        /// AL status code of a slave, explaining a refused or failed state transition.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum AlStatusCode {
+            $(
+                #[doc = $descr]
+                $name,
+            )*
+            /// Vendor specific or unknown code
+            Other(u16),
+        }
+
+        impl From<u16> for AlStatusCode {
+            fn from(code: u16) -> Self {
+                match code {
+                    $($code => AlStatusCode::$name,)*
+                    x => AlStatusCode::Other(x),
+                }
+            }
+        }
+
+        impl From<AlStatusCode> for u16 {
+            fn from(code: AlStatusCode) -> Self {
+                match code {
+                    $(AlStatusCode::$name => $code,)*
+                    AlStatusCode::Other(x) => x,
+                }
+            }
+        }
+
+        impl AlStatusCode {
+            /// Human-readable description of a known code.
+            pub const fn description(self) -> Option<&'static str> {
+                match self {
+                    $(AlStatusCode::$name => Some($descr),)*
+                    AlStatusCode::Other(_) => None,
+                }
+            }
+        }
+    };
+}
+
+al_status_codes! {
+    NoError = 0x0000 => "No error",
+    UnspecifiedError = 0x0001 => "Unspecified error",
+    NoMemory = 0x0002 => "No memory",
+    InvalidDeviceSetup = 0x0003 => "Invalid device setup",
+    InvalidRevision = 0x0004 => "Invalid revision",
+    SiiMismatch = 0x0006 => "SII/EEPROM information does not match firmware",
+    FirmwareUpdateFailed = 0x0007 => "Firmware update not successful, old firmware still running",
+    LicenseError = 0x000E => "License error",
+    InvalidStateChange = 0x0011 => "Invalid requested state change",
+    UnknownState = 0x0012 => "Unknown requested state",
+    BootstrapNotSupported = 0x0013 => "Bootstrap not supported",
+    NoValidFirmware = 0x0014 => "No valid firmware",
+    InvalidBootMailboxConfig = 0x0015 => "Invalid mailbox configuration (BOOT state)",
+    InvalidMailboxConfig = 0x0016 => "Invalid mailbox configuration (PREOP state)",
+    InvalidSyncManagerConfig = 0x0017 => "Invalid sync manager configuration",
+    NoValidInputs = 0x0018 => "No valid inputs available",
+    NoValidOutputs = 0x0019 => "No valid outputs",
+    SyncError = 0x001A => "Synchronization error",
+    SyncManagerWatchdog = 0x001B => "Sync manager watchdog",
+    InvalidSyncManagerTypes = 0x001C => "Invalid sync manager types",
+    InvalidOutputConfig = 0x001D => "Invalid output configuration",
+    InvalidInputConfig = 0x001E => "Invalid input configuration",
+    InvalidWatchdogConfig = 0x001F => "Invalid watchdog configuration",
+    NeedsColdStart = 0x0020 => "Slave needs cold start",
+    NeedsInit = 0x0021 => "Slave needs INIT",
+    NeedsPreOp = 0x0022 => "Slave needs PREOP",
+    NeedsSafeOp = 0x0023 => "Slave needs SAFEOP",
+    InvalidInputMapping = 0x0024 => "Invalid input mapping",
+    InvalidOutputMapping = 0x0025 => "Invalid output mapping",
+    InconsistentSettings = 0x0026 => "Inconsistent settings",
+    FreeRunNotSupported = 0x0027 => "Free-run not supported",
+    SyncModeNotSupported = 0x0028 => "Synchronization not supported",
+    FreeRunNeedsThreeBuffers = 0x0029 => "Free-run needs 3-buffer mode",
+    BackgroundWatchdog = 0x002A => "Background watchdog",
+    NoValidInputsAndOutputs = 0x002B => "No valid inputs and outputs",
+    FatalSyncError = 0x002C => "Fatal sync error",
+    NoSyncError = 0x002D => "No sync error",
+    InvalidInputFmmuConfig = 0x002E => "Invalid input FMMU configuration",
+    InvalidDcSyncConfig = 0x0030 => "Invalid DC SYNC configuration",
+    InvalidDcLatchConfig = 0x0031 => "Invalid DC latch configuration",
+    PllError = 0x0032 => "PLL error",
+    DcSyncIoError = 0x0033 => "DC sync IO error",
+    DcSyncTimeout = 0x0034 => "DC sync timeout error",
+    DcInvalidSyncCycleTime = 0x0035 => "DC invalid sync cycle time",
+    DcSync0CycleTime = 0x0036 => "DC SYNC0 cycle time",
+    DcSync1CycleTime = 0x0037 => "DC SYNC1 cycle time",
+    MailboxAoe = 0x0041 => "Mailbox AoE error",
+    MailboxEoe = 0x0042 => "Mailbox EoE error",
+    MailboxCoe = 0x0043 => "Mailbox CoE error",
+    MailboxFoe = 0x0044 => "Mailbox FoE error",
+    MailboxSoe = 0x0045 => "Mailbox SoE error",
+    MailboxVoe = 0x004F => "Mailbox VoE error",
+    EepromNoAccess = 0x0050 => "EEPROM no access",
+    EepromError = 0x0051 => "EEPROM error",
+    ExternalHardwareNotReady = 0x0052 => "External hardware not ready",
+    RestartedLocally = 0x0060 => "Slave restarted locally",
+    DeviceIdentUpdated = 0x0061 => "Device identification value updated",
+    ModuleIdentMismatch = 0x0070 => "Detected module ident list does not match",
+    SupplyVoltageTooLow = 0x0080 => "Supply voltage too low",
+    SupplyVoltageTooHigh = 0x0081 => "Supply voltage too high",
+    TemperatureTooLow = 0x0082 => "Temperature too low",
+    TemperatureTooHigh = 0x0083 => "Temperature too high",
+    ApplicationControllerAvailable = 0x00F0 => "Application controller available",
+}
+
+impl fmt::Display for AlStatusCode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.description() {
+            Some(d) => write!(f, "0x{:04X} ({})", u16::from(*self), d),
+            None => write!(f, "0x{:04X} (unknown status code)", u16::from(*self)),
+        }
+    }
+}
+
+#[test]
+fn test_al_status_code() {
+    assert_eq!(
+        AlStatusCode::from(0x001D),
+        AlStatusCode::InvalidOutputConfig
+    );
+    assert_eq!(AlStatusCode::from(0x8001), AlStatusCode::Other(0x8001));
+    assert_eq!(u16::from(AlStatusCode::FatalSyncError), 0x002C);
+    assert_eq!(
+        AlStatusCode::FreeRunNotSupported.to_string(),
+        "0x0027 (Free-run not supported)"
+    );
+    assert_eq!(
+        AlStatusCode::Other(0x8001).to_string(),
+        "0x8001 (unknown status code)"
+    );
+}
@@ -6,27 +6,57 @@ use std::ffi::CStr;
 use std::io;
 use std::os::raw::c_char;
 
+#[cfg(not(feature = "sncn"))]
 pub(crate) fn string_to_foe_name(input: &str) -> Result<[c_char; 32]> {
-    if input.len() > 32 {
+    string_to_c_array("FoE name", input, 32)
+}
+
+#[cfg(feature = "sncn")]
+pub(crate) fn string_to_foe_name(input: &str) -> Result<[c_char; 255]> {
+    // leave room for the terminating NUL
+    string_to_c_array("FoE name", input, 254)
+}
+
+#[cfg(not(feature = "sncn"))]
+pub(crate) fn string_to_eoe_name(input: &str) -> Result<[c_char; 32]> {
+    // leave room for the terminating NUL
+    string_to_c_array("EoE host name", input, 31)
+}
+
+#[cfg(not(feature = "sncn"))]
+pub(crate) fn string_to_flag_key(input: &str) -> Result<[c_char; 128]> {
+    // leave room for the terminating NUL
+    string_to_c_array("Flag key", input, 127)
+}
+
+fn string_to_c_array<const N: usize>(
+    what: &str,
+    input: &str,
+    max_len: usize,
+) -> Result<[c_char; N]> {
+    if input.len() > max_len {
         let e = io::Error::new(
             io::ErrorKind::InvalidInput,
             format!(
-                "FoE name can have a maximum length of 32, '{}' has {}",
+                "{} can have a maximum length of {}, '{}' has {}",
+                what,
+                max_len,
                 input,
                 input.len()
             ),
         );
         return Err(Error::Io(e));
     }
-    let mut foe_name: [std::os::raw::c_char; 32] = [0; 32];
+    let mut array: [c_char; N] = [0; N];
     input
         .as_bytes()
         .iter()
-        .zip(&mut foe_name)
+        .zip(&mut array)
         .for_each(|(i, r)| *r = *i as _);
-    Ok(foe_name)
+    Ok(array)
 }
 
+#[cfg(not(feature = "sncn"))]
 #[test]
 fn test_string_to_foe_name() {
     let cmp = |s: String, chars: [i8; 32]| {
@@ -60,6 +90,25 @@ fn test_string_to_foe_name() {
     );
 }
 
+#[cfg(feature = "sncn")]
+#[test]
+fn test_string_to_foe_name() {
+    let name = "a".repeat(254);
+    let chars = string_to_foe_name(&name).expect("Name is ok");
+    assert!(chars[..254].iter().all(|c| *c == b'a' as c_char));
+    assert_eq!(chars[254], 0);
+
+    let name = "a".repeat(255);
+    let e = string_to_foe_name(&name).unwrap_err();
+    assert_eq!(
+        e.to_string(),
+        format!(
+            "FoE name can have a maximum length of 254, '{}' has 255",
+            name
+        )
+    );
+}
+
 pub(crate) fn c_array_to_string(data: *const i8) -> String {
     unsafe {
         CStr::from_ptr(data as *const c_char)
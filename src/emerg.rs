@@ -0,0 +1,118 @@
+// Part of ethercat-rs. Copyright 2018-2022 by the authors.
+// This work is dual-licensed under Apache 2.0 and MIT terms.
+
+//! CoE emergency (EMCY) messages.
+
+use bitflags::bitflags;
+use std::fmt;
+
+/// Size of a CoE emergency message.
+pub(crate) const EMERGENCY_MSG_SIZE: usize = 8;
+
+bitflags! {
+    /// Error register (object 0x1001) sent with an emergency message.
+    #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct ErrorRegister: u8 {
+        const GENERIC = 0x01;
+        const CURRENT = 0x02;
+        const VOLTAGE = 0x04;
+        const TEMPERATURE = 0x08;
+        const COMMUNICATION = 0x10;
+        const DEVICE_PROFILE = 0x20;
+        const MANUFACTURER = 0x80;
+    }
+}
+
+/// A CoE emergency message as defined by CiA 301.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct EmergencyMessage {
+    pub error_code: u16,
+    pub error_register: ErrorRegister,
+    /// Manufacturer specific error data
+    pub data: [u8; 5],
+}
+
+impl EmergencyMessage {
+    pub fn from_bytes(raw: [u8; EMERGENCY_MSG_SIZE]) -> Self {
+        Self {
+            error_code: u16::from_le_bytes([raw[0], raw[1]]),
+            error_register: ErrorRegister::from_bits_retain(raw[2]),
+            data: [raw[3], raw[4], raw[5], raw[6], raw[7]],
+        }
+    }
+
+    /// Description of the error code class.
+    pub const fn description(&self) -> &'static str {
+        match self.error_code {
+            0x0000 => "Error reset or no error",
+            0x8110 => "CAN overrun (objects lost)",
+            0x8120 => "CAN in error passive mode",
+            0x8130 => "Life guard error or heartbeat error",
+            0x8140 => "Recovered from bus off",
+            0x8210 => "PDO not processed due to length error",
+            0x8220 => "PDO length exceeded",
+            0xFF00..=0xFFFF => "Device specific",
+            code => match code & 0xFF00 {
+                0x2100 => "Current, device input side",
+                0x2200 => "Current inside the device",
+                0x2300 => "Current, device output side",
+                0x3100 => "Mains voltage",
+                0x3200 => "Voltage inside the device",
+                0x3300 => "Output voltage",
+                0x4100 => "Ambient temperature",
+                0x4200 => "Device temperature",
+                0x6100 => "Internal software",
+                0x6200 => "User software",
+                0x6300 => "Data set",
+                0x8100 => "Communication",
+                0x8200 => "Protocol error",
+                _ => match code & 0xF000 {
+                    0x1000 => "Generic error",
+                    0x2000 => "Current",
+                    0x3000 => "Voltage",
+                    0x4000 => "Temperature",
+                    0x5000 => "Device hardware",
+                    0x6000 => "Device software",
+                    0x7000 => "Additional modules",
+                    0x8000 => "Monitoring",
+                    0x9000 => "External error",
+                    0xF000 => "Additional functions",
+                    _ => "Unknown error code",
+                },
+            },
+        }
+    }
+}
+
+impl fmt::Display for EmergencyMessage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "EMCY 0x{:04X} ({}), register 0x{:02X}, data {:02X?}",
+            self.error_code,
+            self.description(),
+            self.error_register.bits(),
+            self.data
+        )
+    }
+}
+
+#[test]
+fn test_emergency_message() {
+    let msg = EmergencyMessage::from_bytes([0x10, 0x23, 0x03, 1, 2, 3, 4, 5]);
+    assert_eq!(msg.error_code, 0x2310);
+    assert_eq!(
+        msg.error_register,
+        ErrorRegister::GENERIC | ErrorRegister::CURRENT
+    );
+    assert_eq!(msg.data, [1, 2, 3, 4, 5]);
+    assert_eq!(msg.description(), "Current, device output side");
+    assert_eq!(
+        EmergencyMessage::from_bytes([0x30, 0x81, 0, 0, 0, 0, 0, 0]).description(),
+        "Life guard error or heartbeat error"
+    );
+    assert_eq!(
+        EmergencyMessage::from_bytes([0x00, 0x50, 0, 0, 0, 0, 0, 0]).description(),
+        "Device hardware"
+    );
+}
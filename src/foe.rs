@@ -0,0 +1,102 @@
+// Part of ethercat-rs. Copyright 2018-2022 by the authors.
+// This work is dual-licensed under Apache 2.0 and MIT terms.
+
+//! File access over EtherCAT (FoE) results and error codes.
+
+use std::fmt;
+
+/// Result of an FoE transfer as reported by the master.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FoeResult {
+    Busy,
+    Ready,
+    Idle,
+    WorkingCounterError,
+    ReceiveError,
+    ProtocolError,
+    NoDataError,
+    PacketNumberError,
+    OpcodeError,
+    TimeoutError,
+    SendRxDataError,
+    RxDataAckError,
+    AckError,
+    MailboxFetchError,
+    ReadNoDataError,
+    MailboxProtocolError,
+    /// The file is larger than the buffer
+    ReadOverflowError,
+    Other(u32),
+}
+
+impl From<u32> for FoeResult {
+    fn from(result: u32) -> Self {
+        match result {
+            0 => FoeResult::Busy,
+            1 => FoeResult::Ready,
+            2 => FoeResult::Idle,
+            3 => FoeResult::WorkingCounterError,
+            4 => FoeResult::ReceiveError,
+            5 => FoeResult::ProtocolError,
+            6 => FoeResult::NoDataError,
+            7 => FoeResult::PacketNumberError,
+            8 => FoeResult::OpcodeError,
+            9 => FoeResult::TimeoutError,
+            10 => FoeResult::SendRxDataError,
+            11 => FoeResult::RxDataAckError,
+            12 => FoeResult::AckError,
+            13 => FoeResult::MailboxFetchError,
+            14 => FoeResult::ReadNoDataError,
+            15 => FoeResult::MailboxProtocolError,
+            16 => FoeResult::ReadOverflowError,
+            x => FoeResult::Other(x),
+        }
+    }
+}
+
+/// FoE error code as sent by a slave.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FoeErrorCode(pub u32);
+
+impl FoeErrorCode {
+    /// Human-readable description of a known error code.
+    pub fn description(self) -> Option<&'static str> {
+        Some(match self.0 {
+            0x8000 => "Not defined",
+            0x8001 => "Not found",
+            0x8002 => "Access denied",
+            0x8003 => "Disk full",
+            0x8004 => "Illegal",
+            0x8005 => "Packet number wrong",
+            0x8006 => "Already exists",
+            0x8007 => "No user",
+            0x8008 => "Bootstrap only",
+            0x8009 => "Not bootstrap",
+            0x800A => "No rights",
+            0x800B => "Program error",
+            _ => return None,
+        })
+    }
+}
+
+impl fmt::Display for FoeErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.description() {
+            Some(d) => write!(f, "0x{:04X} ({})", self.0, d),
+            None => write!(f, "0x{:04X} (unknown error code)", self.0),
+        }
+    }
+}
+
+#[test]
+fn test_foe_result_from_u32() {
+    assert_eq!(FoeResult::from(1), FoeResult::Ready);
+    assert_eq!(FoeResult::from(16), FoeResult::ReadOverflowError);
+    assert_eq!(FoeResult::from(42), FoeResult::Other(42));
+}
+
+#[test]
+fn test_foe_error_code_display() {
+    assert_eq!(FoeErrorCode(0x8001).to_string(), "0x8001 (Not found)");
+    assert_eq!(FoeErrorCode(0x1).to_string(), "0x0001 (unknown error code)");
+}
@@ -3,11 +3,32 @@
 
 use ethercat_sys as ec;
 
+mod al_status;
 mod convert;
+mod emerg;
+mod foe;
+#[cfg(feature = "sncn")]
+mod mailbox_gateway;
 mod master;
+mod pcap;
+mod sdo;
+mod sii;
+mod soe;
 mod types;
 
+#[cfg(not(feature = "sncn"))]
+pub use self::master::SoeRequest;
+#[cfg(feature = "sncn")]
+pub use self::{mailbox_gateway::*, master::FoeRequest};
+
 pub use self::{
-    master::{Domain, Master, MasterAccess, SlaveConfig},
+    al_status::*,
+    emerg::{EmergencyMessage, ErrorRegister},
+    foe::*,
+    master::{Domain, Master, MasterAccess, RegRequest, SdoRequest, SlaveConfig, VoeHandler},
+    pcap::*,
+    sdo::*,
+    sii::*,
+    soe::*,
     types::*,
 };
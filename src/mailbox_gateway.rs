@@ -0,0 +1,101 @@
+// Part of ethercat-rs. Copyright 2018-2022 by the authors.
+// This work is dual-licensed under Apache 2.0 and MIT terms.
+
+//! A minimal ETG.8200 mailbox gateway server.
+//!
+//! Engineering tools send mailbox requests encapsulated in an EtherCAT frame
+//! header over UDP; the server forwards them to the addressed slave with
+//! [`Master::mailbox_gateway_frame`] and returns the response the same way.
+
+use crate::{master::Master, types::Result};
+use std::net::{SocketAddr, ToSocketAddrs, UdpSocket};
+
+/// UDP port for EtherCAT frames (0x88A4).
+pub const MAILBOX_GATEWAY_PORT: u16 = 0x88A4;
+
+/// EtherCAT frame header type for mailbox gateway frames.
+const FRAME_TYPE_MAILBOX: u16 = 5;
+const FRAME_HEADER_SIZE: usize = 2;
+
+/// UDP server forwarding mailbox requests of engineering tools to slaves,
+/// as specified by ETG.8200 (Mailbox Gateway).
+///
+/// Requests are forwarded unchanged. ETG.8200 addresses a slave by the
+/// station address in the mailbox header, which the master assigns as ring
+/// position + 1 when it scans the bus (see the `ec_slave_init` call in the
+/// master's `fsm_master.c`).
+pub struct MailboxGateway {
+    socket: UdpSocket,
+}
+
+impl MailboxGateway {
+    /// Listen for requests on the given address, usually port
+    /// [`MAILBOX_GATEWAY_PORT`].
+    pub fn bind<A: ToSocketAddrs>(addr: A) -> Result<Self> {
+        Ok(Self {
+            socket: UdpSocket::bind(addr)?,
+        })
+    }
+
+    pub fn local_addr(&self) -> Result<SocketAddr> {
+        Ok(self.socket.local_addr()?)
+    }
+
+    /// Serve requests until an I/O error occurs on the socket.
+    pub fn run(&self, master: &Master) -> Result<()> {
+        loop {
+            self.serve_one(master)?;
+        }
+    }
+
+    /// Wait for a single request and answer it.
+    ///
+    /// Malformed requests and failed transfers are logged and not answered,
+    /// the client will time out.
+    pub fn serve_one(&self, master: &Master) -> Result<()> {
+        let mut buf = [0; 1536];
+        let (len, client) = self.socket.recv_from(&mut buf)?;
+        let request = match parse_frame(&buf[..len]) {
+            Some(request) => request,
+            None => {
+                log::warn!("Ignoring malformed mailbox gateway request from {}", client);
+                return Ok(());
+            }
+        };
+        match master.mailbox_gateway_frame(request) {
+            Ok(response) => {
+                self.socket.send_to(&build_frame(&response), client)?;
+            }
+            Err(e) => log::warn!("Mailbox gateway request from {} failed: {}", client, e),
+        }
+        Ok(())
+    }
+}
+
+/// Return the mailbox contained in an EtherCAT frame of type mailbox.
+fn parse_frame(frame: &[u8]) -> Option<&[u8]> {
+    let header = u16::from_le_bytes([*frame.first()?, *frame.get(1)?]);
+    let len = usize::from(header & 0x07FF);
+    if header >> 12 != FRAME_TYPE_MAILBOX {
+        return None;
+    }
+    frame.get(FRAME_HEADER_SIZE..FRAME_HEADER_SIZE + len)
+}
+
+fn build_frame(mailbox: &[u8]) -> Vec<u8> {
+    let header = (FRAME_TYPE_MAILBOX << 12) | (mailbox.len() as u16 & 0x07FF);
+    let mut frame = Vec::with_capacity(FRAME_HEADER_SIZE + mailbox.len());
+    frame.extend_from_slice(&header.to_le_bytes());
+    frame.extend_from_slice(mailbox);
+    frame
+}
+
+#[test]
+fn test_mailbox_gateway_frame() {
+    let mailbox = [0x0A, 0x00, 0xE9, 0x03, 0x00, 0x03, 1, 2];
+    let frame = build_frame(&mailbox);
+    assert_eq!(&frame[..2], &[0x08, 0x50]);
+    assert_eq!(parse_frame(&frame), Some(&mailbox[..]));
+    assert_eq!(parse_frame(&frame[..5]), None);
+    assert_eq!(parse_frame(&[0x08, 0x10, 0, 0]), None);
+}
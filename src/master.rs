@@ -3,8 +3,23 @@
 
 #![allow(clippy::field_reassign_with_default)]
 
-use crate::{convert, ec, types::*};
+#[cfg(feature = "sncn")]
+use crate::pcap::{parse_pcap, PcapRecord};
+#[cfg(feature = "sncn")]
+use crate::sdo::decode_complete_access;
+use crate::{
+    al_status::AlStatusCode,
+    convert, ec,
+    emerg::{EmergencyMessage, EMERGENCY_MSG_SIZE},
+    foe::{FoeErrorCode, FoeResult},
+    sdo::{decode_sdo_value, encode_sdo_value, SdoAbortCode, SdoValue},
+    sii::SiiMailbox,
+    soe::{Idn, SoeErrorCode},
+    types::*,
+};
 use num_traits::cast::FromPrimitive;
+#[cfg(not(feature = "sncn"))]
+use std::net::Ipv4Addr;
 use std::{
     collections::HashMap,
     convert::TryFrom,
@@ -12,8 +27,28 @@ use std::{
     fs::{File, OpenOptions},
     io,
     os::{raw::c_ulong, unix::io::AsRawFd},
+    time::Duration,
 };
 
+const REG_ERROR_COUNTERS: u16 = 0x0300;
+const REG_AL_STATUS: u16 = 0x0130;
+const REG_AL_STATUS_CODE: u16 = 0x0134;
+const REG_DC_SYSTEM_TIME: u16 = 0x0910;
+
+/// Mailbox header size and the maximum size of a mailbox gateway frame.
+#[cfg(feature = "sncn")]
+const MAILBOX_HEADER_SIZE: usize = 6;
+#[cfg(feature = "sncn")]
+const MAILBOX_GATEWAY_BUFFER_SIZE: usize = 1500;
+
+/// Size of the buffer the master reads a file into in `foe_read`.
+const FOE_READ_SIZE: usize = 10_000;
+/// `FOE_READY` of `ec_foe_error_t`, not exported by all bindings.
+const FOE_READY: u32 = 1;
+
+/// Upper limit for growing the buffer in `sdo_upload_vec`.
+const MAX_SDO_UPLOAD_SIZE: usize = 1 << 20;
+
 macro_rules! ioctl {
     ($m:expr, $f:expr) => { ioctl!($m, $f,) };
     ($m:expr, $f:expr, $($arg:tt)*) => {{
@@ -81,7 +116,7 @@ impl Master {
         Ok((ioctl!(self, ec::ioctl::CREATE_DOMAIN)? as usize).into())
     }
 
-    pub const fn domain(&self, idx: DomainIdx) -> Domain {
+    pub const fn domain(&self, idx: DomainIdx) -> Domain<'_> {
         Domain::new(idx, self)
     }
 
@@ -113,9 +148,30 @@ impl Master {
         let mut data = ec::ec_ioctl_master_activate_t::default();
         ioctl!(self, ec::ioctl::ACTIVATE, &mut data)?;
 
+        if self.map.is_none() {
+            self.map_process_data(data.process_data_size as _)?;
+        }
+        Ok(())
+    }
+
+    /// Allocate and map the memory of all domains before activation.
+    ///
+    /// Afterwards the process data is available through
+    /// [`domain_data`](Self::domain_data) and [`process_data`](Self::process_data),
+    /// e.g. to initialize outputs or share the memory with other code, and
+    /// [`activate`](Self::activate) keeps this mapping.
+    #[cfg(feature = "sncn")]
+    pub fn setup_domain_memory(&mut self) -> Result<()> {
+        log::debug!("Setup EtherCAT domain memory");
+        let mut data = ec::ec_ioctl_master_activate_t::default();
+        ioctl!(self, ec::ioctl::SETUP_DOMAIN_MEMORY, &mut data)?;
+        self.map_process_data(data.process_data_size as _)
+    }
+
+    fn map_process_data(&mut self, size: usize) -> Result<()> {
         self.map = unsafe {
             memmap::MmapOptions::new()
-                .len(data.process_data_size)
+                .len(size)
                 .map_mut(&self.file)
                 .map(Some)?
         };
@@ -123,6 +179,11 @@ impl Master {
         Ok(())
     }
 
+    /// The whole mapped process image, containing the data of all domains.
+    pub fn process_data(&mut self) -> Result<&mut [u8]> {
+        Ok(&mut self.map.as_mut().ok_or(Error::NotActivated)?[..])
+    }
+
     pub fn deactivate(&mut self) -> Result<()> {
         log::debug!("Deactivate EtherCAT Master");
         ioctl!(self, ec::ioctl::DEACTIVATE)?;
@@ -131,6 +192,13 @@ impl Master {
         Ok(())
     }
 
+    /// Deactivate the slaves while the master itself stays active.
+    #[cfg(feature = "sncn")]
+    pub fn deactivate_slaves(&mut self) -> Result<()> {
+        log::debug!("Deactivate EtherCAT slaves");
+        ioctl!(self, ec::ioctl::DEACTIVATE_SLAVES).map(|_| ())
+    }
+
     pub fn set_send_interval(&mut self, interval_us: usize) -> Result<()> {
         ioctl!(self, ec::ioctl::SET_SEND_INTERVAL, &interval_us).map(|_| ())
     }
@@ -141,6 +209,16 @@ impl Master {
         Ok(sent)
     }
 
+    /// Send the non-application datagrams queued by the master.
+    ///
+    /// This is needed when the application takes over the sending of
+    /// frames, e.g. when the master's EoE and slave requests are processed
+    /// from the cyclic task.
+    #[cfg(feature = "sncn")]
+    pub fn send_ext(&mut self) -> Result<()> {
+        ioctl!(self, ec::ioctl::SEND_EXT, 0).map(|_| ())
+    }
+
     pub fn receive(&mut self) -> Result<()> {
         ioctl!(self, ec::ioctl::RECEIVE).map(|_| ())
     }
@@ -174,23 +252,58 @@ impl Master {
     }
 
     pub fn get_info(&self) -> Result<MasterInfo> {
+        // Frame rates are reported in 1/1000 frames/s.
+        const FRAME_RATE_SCALE: f64 = 1000.0;
         let mut data = ec::ec_ioctl_master_t::default();
         ioctl!(self, ec::ioctl::MASTER, &mut data)?;
-        let ec::ec_ioctl_master_t {
-            slave_count,
-            devices,
-            scan_busy,
-            app_time,
-            ..
-        } = data;
-        let first_device = devices.first().ok_or(Error::NoDevices)?;
+        let first_device = data.devices.first().ok_or(Error::NoDevices)?;
         let link_up = first_device.link_state != 0;
-        let scan_busy = scan_busy != 0;
+        let devices = data
+            .devices
+            .iter()
+            .take(data.num_devices as usize)
+            .map(|d| DeviceInfo {
+                address: MacAddr(d.address),
+                attached: d.attached != 0,
+                link_up: d.link_state != 0,
+                tx_count: d.tx_count,
+                rx_count: d.rx_count,
+                tx_bytes: d.tx_bytes,
+                rx_bytes: d.rx_bytes,
+                tx_errors: d.tx_errors,
+                tx_frame_rate: Rate::from_raw(d.tx_frame_rates, FRAME_RATE_SCALE),
+                rx_frame_rate: Rate::from_raw(d.rx_frame_rates, FRAME_RATE_SCALE),
+                tx_byte_rate: Rate::from_raw(d.tx_byte_rates, 1.0),
+                rx_byte_rate: Rate::from_raw(d.rx_byte_rates, 1.0),
+            })
+            .collect();
         Ok(MasterInfo {
-            slave_count,
+            slave_count: data.slave_count,
+            config_count: data.config_count,
+            domain_count: data.domain_count,
+            eoe_handler_count: data.eoe_handler_count,
+            phase: MasterPhase::from(data.phase),
+            active: data.active != 0,
             link_up,
-            scan_busy,
-            app_time,
+            scan_busy: data.scan_busy != 0,
+            devices,
+            tx_count: data.tx_count,
+            rx_count: data.rx_count,
+            tx_bytes: data.tx_bytes,
+            rx_bytes: data.rx_bytes,
+            tx_frame_rate: Rate::from_raw(data.tx_frame_rates, FRAME_RATE_SCALE),
+            rx_frame_rate: Rate::from_raw(data.rx_frame_rates, FRAME_RATE_SCALE),
+            tx_byte_rate: Rate::from_raw(data.tx_byte_rates, 1.0),
+            rx_byte_rate: Rate::from_raw(data.rx_byte_rates, 1.0),
+            loss_rate: Rate::from_raw(data.loss_rates, FRAME_RATE_SCALE),
+            app_time: data.app_time,
+            dc_ref_time: data.dc_ref_time,
+            ref_clock: match data.ref_clock {
+                0xFFFF => None,
+                pos => Some(SlavePos::from(pos)),
+            },
+            #[cfg(feature = "sncn")]
+            pcap_size: data.pcap_size,
         })
     }
 
@@ -239,6 +352,46 @@ impl Master {
             sync_count: data.sync_count,
             sdo_count: data.sdo_count,
             ports,
+            device_index: data.device_index,
+            boot_rx_mailbox: SiiMailbox {
+                offset: data.boot_rx_mailbox_offset,
+                size: data.boot_rx_mailbox_size,
+            },
+            boot_tx_mailbox: SiiMailbox {
+                offset: data.boot_tx_mailbox_offset,
+                size: data.boot_tx_mailbox_size,
+            },
+            std_rx_mailbox: SiiMailbox {
+                offset: data.std_rx_mailbox_offset,
+                size: data.std_rx_mailbox_size,
+            },
+            std_tx_mailbox: SiiMailbox {
+                offset: data.std_tx_mailbox_offset,
+                size: data.std_tx_mailbox_size,
+            },
+            mailbox_protocols: MailboxProtocols::from_bits_retain(data.mailbox_protocols),
+            has_general_category: data.has_general_category != 0,
+            coe_details: CoeDetails {
+                enable_sdo: data.coe_details.enable_sdo() != 0,
+                enable_sdo_info: data.coe_details.enable_sdo_info() != 0,
+                enable_pdo_assign: data.coe_details.enable_pdo_assign() != 0,
+                enable_pdo_configuration: data.coe_details.enable_pdo_configuration() != 0,
+                enable_upload_at_startup: data.coe_details.enable_upload_at_startup() != 0,
+                enable_sdo_complete_access: data.coe_details.enable_sdo_complete_access() != 0,
+            },
+            general_flags: GeneralFlags {
+                enable_safeop: data.general_flags.enable_safeop() != 0,
+                enable_not_lrw: data.general_flags.enable_not_lrw() != 0,
+            },
+            fmmu_bit: data.fmmu_bit != 0,
+            dc_supported: data.dc_supported != 0,
+            dc_range: DcRange::from(data.dc_range),
+            has_dc_system_time: data.has_dc_system_time != 0,
+            transmission_delay: data.transmission_delay,
+            sii_nwords: data.sii_nwords,
+            group: convert::c_array_to_string(data.group.as_ptr()),
+            image: convert::c_array_to_string(data.image.as_ptr()),
+            order: convert::c_array_to_string(data.order.as_ptr()),
         })
     }
 
@@ -255,6 +408,23 @@ impl Master {
         } else {
             Some(SlavePos::from(data.slave_position as u16))
         };
+        let syncs = data
+            .syncs
+            .iter()
+            .enumerate()
+            .filter(|(_, sync)| sync.config_this != 0 || sync.pdo_count > 0)
+            .map(|(i, sync)| ConfigSmInfo {
+                idx: SmIdx::from(i as u8),
+                configured: sync.config_this != 0,
+                direction: SyncDirection::from(sync.dir),
+                watchdog_mode: WatchdogMode::from(sync.watchdog_mode),
+                pdo_count: sync.pdo_count,
+            })
+            .collect();
+        let dc_sync = [0, 1].map(|i| DcSyncSignal {
+            cycle_time: data.dc_sync[i].cycle_time,
+            shift_time: data.dc_sync[i].shift_time,
+        });
         Ok(ConfigInfo {
             alias: data.alias,
             position: data.position,
@@ -262,10 +432,229 @@ impl Master {
             slave_position,
             sdo_count: data.sdo_count,
             idn_count: data.idn_count,
+            #[cfg(not(feature = "sncn"))]
+            flag_count: data.flag_count,
+            syncs,
+            watchdog_divider: data.watchdog_divider,
+            watchdog_intervals: data.watchdog_intervals,
+            dc_assign_activate: data.dc_assign_activate,
+            dc_sync,
+        })
+    }
+
+    pub fn get_config_pdo(
+        &self,
+        idx: SlaveConfigIdx,
+        sync_index: SmIdx,
+        pdo_pos: PdoPos,
+    ) -> Result<ConfigPdoInfo> {
+        let mut pdo = ec::ec_ioctl_config_pdo_t::default();
+        pdo.config_index = idx;
+        pdo.sync_index = u8::from(sync_index);
+        pdo.pdo_pos = u16::from(u8::from(pdo_pos));
+        ioctl!(self, ec::ioctl::CONFIG_PDO, &mut pdo)?;
+        let entries = (0..pdo.entry_count)
+            .map(|pos| self.get_config_pdo_entry(idx, sync_index, pdo_pos, PdoEntryPos::from(pos)))
+            .collect::<Result<_>>()?;
+        Ok(ConfigPdoInfo {
+            sm: sync_index,
+            pos: pdo_pos,
+            idx: Idx::from(pdo.index),
+            name: convert::c_array_to_string(pdo.name.as_ptr()),
+            entries,
+        })
+    }
+
+    pub fn get_config_pdo_entry(
+        &self,
+        idx: SlaveConfigIdx,
+        sync_index: SmIdx,
+        pdo_pos: PdoPos,
+        entry_pos: PdoEntryPos,
+    ) -> Result<PdoEntryInfo> {
+        let mut entry = ec::ec_ioctl_config_pdo_entry_t::default();
+        entry.config_index = idx;
+        entry.sync_index = u8::from(sync_index);
+        entry.pdo_pos = u16::from(u8::from(pdo_pos));
+        entry.entry_pos = u8::from(entry_pos);
+        ioctl!(self, ec::ioctl::CONFIG_PDO_ENTRY, &mut entry)?;
+        Ok(PdoEntryInfo {
+            pos: entry_pos,
+            entry_idx: PdoEntryIdx {
+                idx: Idx::from(entry.index),
+                sub_idx: SubIdx::from(entry.subindex),
+            },
+            bit_len: entry.bit_length,
+            name: convert::c_array_to_string(entry.name.as_ptr()),
+        })
+    }
+
+    pub fn get_config_sdo(&self, idx: SlaveConfigIdx, sdo_pos: u32) -> Result<ConfigSdoInfo> {
+        let mut sdo = ec::ec_ioctl_config_sdo_t::default();
+        sdo.config_index = idx;
+        sdo.sdo_pos = sdo_pos;
+        ioctl!(self, ec::ioctl::CONFIG_SDO, &mut sdo)?;
+        let size: usize = sdo.size as _;
+        Ok(ConfigSdoInfo {
+            sdo_idx: SdoIdx::new(sdo.index, sdo.subindex),
+            complete_access: sdo.complete_access != 0,
+            size,
+            data: sdo.data[..size.min(sdo.data.len())].to_vec(),
+        })
+    }
+
+    pub fn get_config_idn(&self, idx: SlaveConfigIdx, idn_pos: u32) -> Result<ConfigIdnInfo> {
+        let mut idn = ec::ec_ioctl_config_idn_t::default();
+        idn.config_index = idx;
+        idn.idn_pos = idn_pos;
+        ioctl!(self, ec::ioctl::CONFIG_IDN, &mut idn)?;
+        let al_state_u8 = idn.state as u8;
+        Ok(ConfigIdnInfo {
+            drive_no: idn.drive_no,
+            idn: Idn::new(idn.idn),
+            al_state: AlState::try_from(al_state_u8)
+                .map_err(|_| Error::InvalidAlState(al_state_u8))?,
+            data: idn.data[..idn.size.min(idn.data.len())].to_vec(),
+        })
+    }
+
+    #[cfg(not(feature = "sncn"))]
+    pub fn get_config_flag(&self, idx: SlaveConfigIdx, flag_pos: u32) -> Result<ConfigFlagInfo> {
+        let mut flag = ec::ec_ioctl_config_flag_t::default();
+        flag.config_index = idx;
+        flag.flag_pos = flag_pos;
+        ioctl!(self, ec::ioctl::CONFIG_FLAG, &mut flag)?;
+        Ok(ConfigFlagInfo {
+            key: SlaveConfigFlag::from(convert::c_array_to_string(flag.key.as_ptr()).as_str()),
+            value: flag.value,
+        })
+    }
+
+    /// Iterate over the PDOs (with their entries) stored in a slave config.
+    pub fn config_pdos(
+        &self,
+        idx: SlaveConfigIdx,
+    ) -> Result<impl Iterator<Item = Result<ConfigPdoInfo>> + '_> {
+        let syncs = self
+            .get_config_info(idx)?
+            .syncs
+            .into_iter()
+            .map(|sync| {
+                let count = u8::try_from(sync.pdo_count).map_err(|_| {
+                    Error::Io(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!(
+                            "{} PDOs assigned to sync manager {}",
+                            sync.pdo_count,
+                            u8::from(sync.idx)
+                        ),
+                    ))
+                })?;
+                Ok((sync.idx, count))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(syncs.into_iter().flat_map(move |(sm, count)| {
+            (0..count).map(move |pos| self.get_config_pdo(idx, sm, PdoPos::from(pos)))
+        }))
+    }
+
+    /// Iterate over the SDOs stored in a slave config.
+    pub fn config_sdos(
+        &self,
+        idx: SlaveConfigIdx,
+    ) -> Result<impl Iterator<Item = Result<ConfigSdoInfo>> + '_> {
+        let info = self.get_config_info(idx)?;
+        Ok((0..info.sdo_count).map(move |pos| self.get_config_sdo(idx, pos)))
+    }
+
+    /// Iterate over the IDNs stored in a slave config.
+    pub fn config_idns(
+        &self,
+        idx: SlaveConfigIdx,
+    ) -> Result<impl Iterator<Item = Result<ConfigIdnInfo>> + '_> {
+        let info = self.get_config_info(idx)?;
+        Ok((0..info.idn_count).map(move |pos| self.get_config_idn(idx, pos)))
+    }
+
+    /// Iterate over the feature flags stored in a slave config.
+    #[cfg(not(feature = "sncn"))]
+    pub fn config_flags(
+        &self,
+        idx: SlaveConfigIdx,
+    ) -> Result<impl Iterator<Item = Result<ConfigFlagInfo>> + '_> {
+        let info = self.get_config_info(idx)?;
+        Ok((0..info.flag_count).map(move |pos| self.get_config_flag(idx, pos)))
+    }
+
+    pub fn get_domain_info(&self, idx: DomainIdx) -> Result<DomainInfo> {
+        let mut data = ec::ec_ioctl_domain_t {
+            index: u32::try_from(idx).map_err(|_| Error::DomainIdx(usize::from(idx)))?,
+            ..Default::default()
+        };
+        ioctl!(self, ec::ioctl::DOMAIN, &mut data)?;
+        Ok(DomainInfo {
+            data_size: data.data_size as usize,
+            logical_base_address: data.logical_base_address,
+            working_counter: data.working_counter.iter().sum(),
+            expected_working_counter: data.expected_working_counter,
+            fmmu_count: data.fmmu_count,
         })
     }
 
-    pub fn configure_slave(&mut self, addr: SlaveAddr, expected: SlaveId) -> Result<SlaveConfig> {
+    pub fn get_domain_fmmus(&self, idx: DomainIdx) -> Result<Vec<DomainFmmuInfo>> {
+        let info = self.get_domain_info(idx)?;
+        (0..info.fmmu_count)
+            .map(|fmmu_index| {
+                let mut data = ec::ec_ioctl_domain_fmmu_t {
+                    domain_index: u32::try_from(idx)
+                        .map_err(|_| Error::DomainIdx(usize::from(idx)))?,
+                    fmmu_index,
+                    ..Default::default()
+                };
+                ioctl!(self, ec::ioctl::DOMAIN_FMMU, &mut data)?;
+                Ok(DomainFmmuInfo {
+                    config_alias: data.slave_config_alias,
+                    config_position: data.slave_config_position,
+                    sync_idx: SmIdx::from(data.sync_index),
+                    direction: SyncDirection::from(data.dir),
+                    logical_address: data.logical_address,
+                    data_size: data.data_size as usize,
+                })
+            })
+            .collect()
+    }
+
+    /// Copy the current process data of a domain into `target`.
+    ///
+    /// Unlike [`domain_data`](Self::domain_data) this does not need the
+    /// memory mapping of an activated master and therefore also works with
+    /// [`MasterAccess::ReadOnly`]. The length of `target` must match the
+    /// domain's data size (see [`get_domain_info`](Self::get_domain_info)).
+    pub fn read_domain_data(&self, idx: DomainIdx, target: &mut [u8]) -> Result<()> {
+        let info = self.get_domain_info(idx)?;
+        if target.len() != info.data_size {
+            return Err(Error::Io(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "buffer of {} bytes does not match domain data size ({} bytes)",
+                    target.len(),
+                    info.data_size
+                ),
+            )));
+        }
+        let mut data = ec::ec_ioctl_domain_data_t {
+            domain_index: u32::try_from(idx).map_err(|_| Error::DomainIdx(usize::from(idx)))?,
+            data_size: info.data_size as u32,
+            target: target.as_mut_ptr(),
+        };
+        ioctl!(self, ec::ioctl::DOMAIN_DATA, &mut data).map(|_| ())
+    }
+
+    pub fn configure_slave(
+        &mut self,
+        addr: SlaveAddr,
+        expected: SlaveId,
+    ) -> Result<SlaveConfig<'_>> {
         log::debug!("Configure slave {:?}", addr);
         let mut data = ec::ec_ioctl_config_t::default();
         let (alias, pos) = addr.as_pair();
@@ -368,16 +757,29 @@ impl Master {
             data: data_ptr,
             abort_code: 0,
         };
-        ioctl!(self, ec::ioctl::SLAVE_SDO_DOWNLOAD, &mut data).map(|_| ())
+        ioctl!(self, ec::ioctl::SLAVE_SDO_DOWNLOAD, &mut data)
+            .map_err(|e| sdo_abort(e, position, sdo_idx, data.abort_code))
+            .map(|_| ())
     }
 
+    /// Upload an SDO into `target`, returning the part filled with data.
+    ///
+    /// Complete access is only supported by the `sncn` master, otherwise
+    /// requesting it is an error.
     pub fn sdo_upload<'t>(
         &self,
         position: SlavePos,
         sdo_idx: SdoIdx,
-        #[allow(unused_variables)] complete_access: bool,
+        complete_access: bool,
         target: &'t mut [u8],
     ) -> Result<&'t mut [u8]> {
+        #[cfg(not(feature = "sncn"))]
+        if complete_access {
+            return Err(Error::Io(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "SDO upload with complete access is not supported by this master",
+            )));
+        }
         let slave_position = u16::from(position);
         let sdo_index = u16::from(sdo_idx.idx);
         let sdo_entry_subindex = u8::from(sdo_idx.sub_idx);
@@ -408,10 +810,126 @@ impl Master {
             complete_access: if complete_access { 1 } else { 0 },
         };
 
-        ioctl!(self, ec::ioctl::SLAVE_SDO_UPLOAD, &mut data)?;
+        ioctl!(self, ec::ioctl::SLAVE_SDO_UPLOAD, &mut data)
+            .map_err(|e| sdo_abort(e, position, sdo_idx, data.abort_code))?;
         Ok(&mut target[..data.data_size])
     }
 
+    /// Upload an SDO into a buffer that is grown until the data fits.
+    ///
+    /// As with [`sdo_upload`](Self::sdo_upload), complete access requires
+    /// the `sncn` master.
+    pub fn sdo_upload_vec(
+        &self,
+        position: SlavePos,
+        sdo_idx: SdoIdx,
+        complete_access: bool,
+    ) -> Result<Vec<u8>> {
+        let mut buf = vec![0; ec::EC_MAX_SDO_DATA_SIZE as usize];
+        loop {
+            let result = self
+                .sdo_upload(position, sdo_idx, complete_access, &mut buf)
+                .map(|data| data.len());
+            match result {
+                Ok(len) => {
+                    buf.truncate(len);
+                    return Ok(buf);
+                }
+                Err(Error::Io(e))
+                    if e.raw_os_error() == Some(libc::EOVERFLOW)
+                        && buf.len() < MAX_SDO_UPLOAD_SIZE =>
+                {
+                    let len = buf.len() * 2;
+                    buf.resize(len, 0);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// Read an SDO entry and convert it according to its data type.
+    pub fn sdo_read<T: SdoValue>(&mut self, position: SlavePos, sdo_idx: SdoIdx) -> Result<T> {
+        let entry = self.get_sdo_entry(position, SdoEntryAddr::ByIdx(sdo_idx))?;
+        let data = self.sdo_upload_vec(position, sdo_idx, false)?;
+        let value = decode_sdo_value(entry.data_type, &data)?;
+        T::from_value(value).ok_or_else(|| {
+            Error::InvalidSdoData(format!(
+                "{:?} of SDO {:X}:{} cannot be converted to {}",
+                entry.data_type,
+                u16::from(sdo_idx.idx),
+                u8::from(sdo_idx.sub_idx),
+                std::any::type_name::<T>()
+            ))
+        })
+    }
+
+    /// Convert a value according to the data type of an SDO entry and write it.
+    pub fn sdo_write<T: SdoValue>(
+        &mut self,
+        position: SlavePos,
+        sdo_idx: SdoIdx,
+        value: &T,
+    ) -> Result<()> {
+        let entry = self.get_sdo_entry(position, SdoEntryAddr::ByIdx(sdo_idx))?;
+        let value = value.to_value(entry.data_type).ok_or_else(|| {
+            Error::InvalidSdoData(format!(
+                "{} cannot be written to SDO {:X}:{} of type {:?}",
+                std::any::type_name::<T>(),
+                u16::from(sdo_idx.idx),
+                u8::from(sdo_idx.sub_idx),
+                entry.data_type
+            ))
+        })?;
+        let data = encode_sdo_value(&value);
+        self.sdo_download(position, sdo_idx, false, &data.as_slice())
+    }
+
+    /// Read all entries of a record or array object, starting with subindex 1.
+    ///
+    /// The entries are decoded according to their data type and returned
+    /// with their subindex; subindices the object does not have are skipped.
+    /// With the `sncn` master, the object is uploaded at once using complete
+    /// access, otherwise the entries are read one by one.
+    pub fn sdo_read_record(
+        &mut self,
+        position: SlavePos,
+        idx: Idx,
+    ) -> Result<Vec<(SubIdx, Value)>> {
+        let count: u8 = self.sdo_read(position, SdoIdx::new(u16::from(idx), 0))?;
+        let mut entries = Vec::with_capacity(usize::from(count));
+        for sub in 1..=count {
+            let sdo_idx = SdoIdx::new(u16::from(idx), sub);
+            match self.get_sdo_entry(position, SdoEntryAddr::ByIdx(sdo_idx)) {
+                Ok(entry) if entry.bit_len > 0 => entries.push((sdo_idx.sub_idx, entry)),
+                Ok(_) => {}
+                Err(e) if is_missing_sdo_entry(&e) => {}
+                Err(e) => return Err(e),
+            }
+        }
+        #[cfg(feature = "sncn")]
+        {
+            let data = self.sdo_upload_vec(position, SdoIdx::new(u16::from(idx), 0), true)?;
+            let layout: Vec<_> = entries
+                .iter()
+                .map(|(sub, entry)| (*sub, entry.data_type, entry.bit_len))
+                .collect();
+            decode_complete_access(&layout, &data)
+        }
+        #[cfg(not(feature = "sncn"))]
+        {
+            let mut values = Vec::with_capacity(entries.len());
+            for (sub, entry) in entries {
+                let sdo_idx = SdoIdx::new(u16::from(idx), u8::from(sub));
+                match self.sdo_upload_vec(position, sdo_idx, false) {
+                    Ok(data) => values.push((sub, decode_sdo_value(entry.data_type, &data)?)),
+                    Err(e) if is_missing_sdo_entry(&e) => {}
+                    Err(e) => return Err(e),
+                }
+            }
+            Ok(values)
+        }
+    }
+
     pub fn get_pdo(
         &mut self,
         slave_pos: SlavePos,
@@ -471,6 +989,174 @@ impl Master {
         })
     }
 
+    /// Read `nwords` words of the slave's SII, starting at `offset`.
+    ///
+    /// The result can be decoded with [`Sii::from_words`](crate::Sii::from_words).
+    pub fn sii_read(&self, slave_pos: SlavePos, offset: u16, nwords: u32) -> Result<Vec<u16>> {
+        let mut words = vec![0_u16; nwords as usize];
+        let mut data = ec::ec_ioctl_slave_sii_t {
+            slave_position: u16::from(slave_pos),
+            offset,
+            nwords,
+            words: words.as_mut_ptr(),
+        };
+        ioctl!(self, ec::ioctl::SLAVE_SII_READ, &mut data)?;
+        Ok(words.into_iter().map(u16::from_le).collect())
+    }
+
+    /// Write `words` to the slave's SII, starting at `offset`.
+    ///
+    /// When rewriting the header, make sure that word 0x0007 holds the
+    /// checksum calculated by [`sii_checksum`](crate::sii_checksum).
+    pub fn sii_write(&mut self, slave_pos: SlavePos, offset: u16, words: &[u16]) -> Result<()> {
+        let mut words: Vec<u16> = words.iter().map(|w| w.to_le()).collect();
+        let data = ec::ec_ioctl_slave_sii_t {
+            slave_position: u16::from(slave_pos),
+            offset,
+            nwords: words.len() as u32,
+            words: words.as_mut_ptr(),
+        };
+        ioctl!(self, ec::ioctl::SLAVE_SII_WRITE, &data)?;
+        Ok(())
+    }
+
+    /// Get the state and statistics of all EoE handlers.
+    pub fn eoe_handlers(&self) -> Result<Vec<EoeHandlerInfo>> {
+        let mut master = ec::ec_ioctl_master_t::default();
+        ioctl!(self, ec::ioctl::MASTER, &mut master)?;
+        (0..master.eoe_handler_count)
+            .map(|i| {
+                let mut data = ec::ec_ioctl_eoe_handler_t::default();
+                data.eoe_index = i as u16;
+                ioctl!(self, ec::ioctl::EOE_HANDLER, &mut data)?;
+                Ok(EoeHandlerInfo {
+                    name: convert::c_array_to_string(data.name.as_ptr()),
+                    slave_pos: SlavePos::from(data.slave_position),
+                    open: data.open != 0,
+                    rx_bytes: data.rx_bytes,
+                    rx_rate: data.rx_rate,
+                    tx_bytes: data.tx_bytes,
+                    tx_rate: data.tx_rate,
+                    tx_queued_frames: data.tx_queued_frames,
+                    tx_queue_size: data.tx_queue_size,
+                })
+            })
+            .collect()
+    }
+
+    /// Send IP parameters to an EoE slave via a blocking mailbox transfer.
+    #[cfg(not(feature = "sncn"))]
+    pub fn set_slave_eoe_ip(&mut self, slave_pos: SlavePos, params: &EoeIpParams) -> Result<()> {
+        let mut data = eoe_ip_to_ioctl(params)?;
+        data.slave_position = u16::from(slave_pos);
+        ioctl!(self, ec::ioctl::SLAVE_EOE_IP_PARAM, &data)?;
+        Ok(())
+    }
+
+    /// Get the EoE IP parameters stored in a slave configuration.
+    #[cfg(not(feature = "sncn"))]
+    pub fn get_config_eoe_ip(&self, idx: SlaveConfigIdx) -> Result<EoeIpParams> {
+        let mut data = ec::ec_ioctl_eoe_ip_t::default();
+        data.config_index = idx as u16;
+        ioctl!(self, ec::ioctl::CONFIG_EOE_IP_PARAM, &mut data)?;
+        let addr = |included: u8, addr: ec::in_addr| {
+            (included != 0).then(|| Ipv4Addr::from(addr.s_addr.to_ne_bytes()))
+        };
+        Ok(EoeIpParams {
+            mac_address: (data.mac_address_included != 0).then_some(MacAddr(data.mac_address)),
+            ip_address: addr(data.ip_address_included, data.ip_address),
+            subnet_mask: addr(data.subnet_mask_included, data.subnet_mask),
+            gateway: addr(data.gateway_included, data.gateway),
+            dns: addr(data.dns_included, data.dns),
+            name: (data.name_included != 0).then(|| convert::c_array_to_string(data.name.as_ptr())),
+        })
+    }
+
+    /// Read `target.len()` bytes from the slave's ESC register space,
+    /// starting at `address`.
+    ///
+    /// Emergency requests are sent directly, even if the master is busy.
+    pub fn read_register(
+        &self,
+        slave_pos: SlavePos,
+        address: u16,
+        emergency: bool,
+        target: &mut [u8],
+    ) -> Result<()> {
+        let mut data = ec::ec_ioctl_slave_reg_t {
+            slave_position: u16::from(slave_pos),
+            emergency: emergency as u8,
+            address,
+            size: target.len(),
+            data: target.as_mut_ptr(),
+        };
+        ioctl!(self, ec::ioctl::SLAVE_REG_READ, &mut data)?;
+        Ok(())
+    }
+
+    /// Write `data` to the slave's ESC register space, starting at `address`.
+    pub fn write_register(
+        &mut self,
+        slave_pos: SlavePos,
+        address: u16,
+        emergency: bool,
+        data: &[u8],
+    ) -> Result<()> {
+        let data = ec::ec_ioctl_slave_reg_t {
+            slave_position: u16::from(slave_pos),
+            emergency: emergency as u8,
+            address,
+            size: data.len(),
+            data: data.as_ptr() as *mut _,
+        };
+        ioctl!(self, ec::ioctl::SLAVE_REG_WRITE, &data)?;
+        Ok(())
+    }
+
+    /// Read the RX, forwarded RX, lost link and processing unit error
+    /// counters (registers 0x0300 to 0x0313).
+    pub fn error_counters(&self, slave_pos: SlavePos) -> Result<EscErrorCounters> {
+        let mut regs = [0; 0x14];
+        self.read_register(slave_pos, REG_ERROR_COUNTERS, false, &mut regs)?;
+        let mut ports = [PortErrorCounters::default(); ec::EC_MAX_PORTS as usize];
+        for (i, port) in ports.iter_mut().enumerate() {
+            port.invalid_frame = regs[2 * i];
+            port.rx_error = regs[2 * i + 1];
+            port.forwarded_rx_error = regs[0x08 + i];
+            port.lost_link = regs[0x10 + i];
+        }
+        Ok(EscErrorCounters {
+            ports,
+            processing_unit: regs[0x0C],
+            pdi: regs[0x0D],
+        })
+    }
+
+    /// Read the AL status register (0x0130).
+    pub fn al_status(&self, slave_pos: SlavePos) -> Result<AlStatus> {
+        let mut regs = [0; 2];
+        self.read_register(slave_pos, REG_AL_STATUS, false, &mut regs)?;
+        let state = regs[0] & 0x0F;
+        Ok(AlStatus {
+            state: AlState::try_from(state).map_err(|_| Error::InvalidAlState(state))?,
+            error: regs[0] & 0x10 != 0,
+        })
+    }
+
+    /// Read the AL status code register (0x0134).
+    pub fn al_status_code(&self, slave_pos: SlavePos) -> Result<AlStatusCode> {
+        let mut regs = [0; 2];
+        self.read_register(slave_pos, REG_AL_STATUS_CODE, false, &mut regs)?;
+        Ok(AlStatusCode::from(u16::from_le_bytes(regs)))
+    }
+
+    /// Read the local copy of the DC system time in ns (register 0x0910).
+    pub fn dc_system_time(&self, slave_pos: SlavePos) -> Result<u64> {
+        let mut regs = [0; 8];
+        self.read_register(slave_pos, REG_DC_SYSTEM_TIME, false, &mut regs)?;
+        Ok(u64::from_le_bytes(regs))
+    }
+
     pub fn request_state(&mut self, slave_pos: SlavePos, state: AlState) -> Result<()> {
         let mut data = ec::ec_ioctl_slave_state_t::default();
         data.slave_position = u16::from(slave_pos);
@@ -487,6 +1173,99 @@ impl Master {
         Ok(())
     }
 
+    /// Request a soft reboot of a slave (ESC register 0x0040).
+    #[cfg(feature = "sncn")]
+    pub fn reboot_slave(&mut self, slave_pos: SlavePos) -> Result<()> {
+        let data = ec::ec_ioctl_slave_reboot_t {
+            slave_position: u16::from(slave_pos),
+            broadcast: 0,
+        };
+        ioctl!(self, ec::ioctl::SLAVE_REBOOT, &data).map(|_| ())
+    }
+
+    /// Request a soft reboot of all slaves.
+    #[cfg(feature = "sncn")]
+    pub fn reboot_all(&mut self) -> Result<()> {
+        let data = ec::ec_ioctl_slave_reboot_t {
+            slave_position: 0,
+            broadcast: 1,
+        };
+        ioctl!(self, ec::ioctl::SLAVE_REBOOT, &data).map(|_| ())
+    }
+
+    /// Forward a mailbox request to a slave and return the response.
+    ///
+    /// `request` is a complete mailbox frame (header and data); the address
+    /// field of the header is set to the station address of the slave, as
+    /// required by ETG.8200. The master assigns station addresses as ring
+    /// position + 1 when scanning the bus (`ec_slave_init` in `fsm_master.c`).
+    #[cfg(feature = "sncn")]
+    pub fn mailbox_gateway(&self, slave_pos: SlavePos, request: &[u8]) -> Result<Vec<u8>> {
+        let mut frame = request.to_vec();
+        if frame.len() < MAILBOX_HEADER_SIZE {
+            return Err(Error::Io(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "mailbox frame is shorter than its header",
+            )));
+        }
+        let address = u16::from(slave_pos).checked_add(1).ok_or_else(|| {
+            Error::Io(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "slave position {} has no station address",
+                    u16::from(slave_pos)
+                ),
+            ))
+        })?;
+        frame[2..4].copy_from_slice(&address.to_le_bytes());
+        self.mailbox_gateway_frame(&frame)
+    }
+
+    /// Forward a mailbox frame, addressed by its header, and return the response.
+    #[cfg(feature = "sncn")]
+    pub fn mailbox_gateway_frame(&self, frame: &[u8]) -> Result<Vec<u8>> {
+        if frame.len() > MAILBOX_GATEWAY_BUFFER_SIZE {
+            return Err(Error::Io(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "mailbox frame of {} bytes exceeds {} bytes",
+                    frame.len(),
+                    MAILBOX_GATEWAY_BUFFER_SIZE
+                ),
+            )));
+        }
+        let mut buf = vec![0; MAILBOX_GATEWAY_BUFFER_SIZE];
+        buf[..frame.len()].copy_from_slice(frame);
+        let mut data = ec::ec_ioctl_mbox_gateway_t {
+            data_size: frame.len() as _,
+            buff_size: buf.len() as _,
+            data: buf.as_mut_ptr(),
+        };
+        ioctl!(self, ec::ioctl::MBOX_GATEWAY, &mut data)?;
+        buf.truncate(data.data_size as usize);
+        Ok(buf)
+    }
+
+    /// Fetch the frames captured by the master.
+    ///
+    /// The master keeps a libpcap image of the sent and received frames;
+    /// its records can be written to a file with [`PcapWriter`](crate::PcapWriter).
+    /// With `reset`, the capture buffer is cleared afterwards.
+    #[cfg(feature = "sncn")]
+    pub fn pcap_snapshot(&self, reset: bool) -> Result<Vec<PcapRecord>> {
+        let mut master = ec::ec_ioctl_master_t::default();
+        ioctl!(self, ec::ioctl::MASTER, &mut master)?;
+        let mut buf = vec![0; master.pcap_size as usize];
+        let mut data = ec::ec_ioctl_pcap_data_t {
+            data_size: master.pcap_size,
+            reset_data: u8::from(reset),
+            target: buf.as_mut_ptr(),
+        };
+        ioctl!(self, ec::ioctl::PCAP_DATA, &mut data)?;
+        buf.truncate(data.data_size as usize);
+        parse_pcap(&buf)
+    }
+
     pub fn set_application_time(&mut self, app_time: u64) -> Result<()> {
         ioctl!(self, ec::ioctl::APP_TIME, &app_time)?;
         Ok(())
@@ -524,24 +1303,28 @@ impl Master {
         Ok(time)
     }
 
+    /// Read a file from a slave.
+    ///
+    /// Files larger than 10000 bytes fail with `FoeResult::ReadOverflowError`.
+    /// With the `sncn` master, they can be read with an `FoeRequest`.
     pub fn foe_read(&mut self, idx: SlavePos, name: &str) -> Result<Vec<u8>> {
         let file_name = convert::string_to_foe_name(name)?;
         // FIXME: this is the same as in the c-implementation. Should read in chunks instead of a
         // fixed size buffer. The ioctl-call in the master pre-allocates a 10000 byte buffer, so we
         // do the same here.
-        const FOE_SIZE: usize = 10_000;
-        let mut buf: Vec<u8> = vec![0; FOE_SIZE];
+        let mut buf: Vec<u8> = vec![0; FOE_READ_SIZE];
         let mut data = ec::ec_ioctl_slave_foe_t {
             slave_position: idx.into(),
             offset: 0,
-            buffer_size: FOE_SIZE,
+            buffer_size: buf.len(),
             buffer: buf.as_mut_ptr(),
             file_name,
+            // plain I/O error if the master does not report a result
+            result: FOE_READY,
             ..Default::default()
         };
-        ioctl!(self, ec::ioctl::SLAVE_FOE_READ, &mut data)?;
-
-        assert!(data.data_size <= FOE_SIZE);
+        ioctl!(self, ec::ioctl::SLAVE_FOE_READ, &mut data)
+            .map_err(|e| foe_error(e, idx, data.result, data.error_code))?;
         buf.truncate(data.data_size);
         Ok(buf)
     }
@@ -550,20 +1333,154 @@ impl Master {
         let file_name = convert::string_to_foe_name(name)?;
 
         let buffer = data.as_ptr() as *mut _;
-        let data = ec::ec_ioctl_slave_foe_t {
+        let mut data = ec::ec_ioctl_slave_foe_t {
             slave_position: idx.into(),
             offset: 0,
             buffer_size: data.len(),
             buffer,
             file_name,
+            // plain I/O error if the master does not report a result
+            result: FOE_READY,
             ..Default::default()
         };
-        ioctl!(self, ec::ioctl::SLAVE_FOE_WRITE, &data)?;
+        ioctl!(self, ec::ioctl::SLAVE_FOE_WRITE, &mut data)
+            .map_err(|e| foe_error(e, idx, data.result, data.error_code))?;
 
         Ok(())
     }
 
-    // XXX missing: write_idn, read_idn
+    /// Read the value of an IDN from a SoE drive.
+    pub fn read_idn(&mut self, slave_pos: SlavePos, drive_no: u8, idn: Idn) -> Result<Vec<u8>> {
+        let mut buf = vec![0; ec::EC_MAX_IDN_DATA_SIZE as usize];
+        let mut data = ec::ec_ioctl_slave_soe_read_t {
+            slave_position: u16::from(slave_pos),
+            drive_no,
+            idn: u16::from(idn),
+            mem_size: buf.len(),
+            data: buf.as_mut_ptr(),
+            data_size: 0,
+            error_code: 0,
+        };
+        ioctl!(self, ec::ioctl::SLAVE_SOE_READ, &mut data)
+            .map_err(|e| soe_error(e, slave_pos, idn, data.error_code))?;
+        buf.truncate(data.data_size);
+        Ok(buf)
+    }
+
+    /// Write the value of an IDN to a SoE drive.
+    pub fn write_idn(
+        &mut self,
+        slave_pos: SlavePos,
+        drive_no: u8,
+        idn: Idn,
+        data: &[u8],
+    ) -> Result<()> {
+        let mut data = ec::ec_ioctl_slave_soe_write_t {
+            slave_position: u16::from(slave_pos),
+            drive_no,
+            idn: u16::from(idn),
+            data_size: data.len(),
+            data: data.as_ptr() as *mut _,
+            error_code: 0,
+        };
+        ioctl!(self, ec::ioctl::SLAVE_SOE_WRITE, &mut data)
+            .map_err(|e| soe_error(e, slave_pos, idn, data.error_code))?;
+        Ok(())
+    }
+}
+
+#[cfg(not(feature = "sncn"))]
+fn eoe_ip_to_ioctl(params: &EoeIpParams) -> Result<ec::ec_ioctl_eoe_ip_t> {
+    let mut data = ec::ec_ioctl_eoe_ip_t::default();
+    let addr = |ip: Ipv4Addr| ec::in_addr {
+        s_addr: u32::from_ne_bytes(ip.octets()),
+    };
+    if let Some(mac) = params.mac_address {
+        data.mac_address_included = 1;
+        data.mac_address = mac.0;
+    }
+    if let Some(ip) = params.ip_address {
+        data.ip_address_included = 1;
+        data.ip_address = addr(ip);
+    }
+    if let Some(ip) = params.subnet_mask {
+        data.subnet_mask_included = 1;
+        data.subnet_mask = addr(ip);
+    }
+    if let Some(ip) = params.gateway {
+        data.gateway_included = 1;
+        data.gateway = addr(ip);
+    }
+    if let Some(ip) = params.dns {
+        data.dns_included = 1;
+        data.dns = addr(ip);
+    }
+    if let Some(name) = &params.name {
+        data.name_included = 1;
+        data.name = convert::string_to_eoe_name(name)?;
+    }
+    Ok(data)
+}
+
+fn pop_emerg(master: &Master, idx: SlaveConfigIdx) -> Result<Option<EmergencyMessage>> {
+    let mut raw = [0; EMERGENCY_MSG_SIZE];
+    let mut data = ec::ec_ioctl_sc_emerg_t::default();
+    data.config_index = idx;
+    data.target = raw.as_mut_ptr();
+    match ioctl!(master, ec::ioctl::SC_EMERG_POP, &mut data) {
+        Ok(_) => Ok(Some(EmergencyMessage::from_bytes(raw))),
+        // the ring buffer is empty
+        Err(Error::Io(e)) if e.raw_os_error() == Some(libc::ENOENT) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// The master reports failed FoE transfers as `EIO` and sets the result.
+/// Whether an SDO access failed because the entry does not exist.
+fn is_missing_sdo_entry(e: &Error) -> bool {
+    match e {
+        // not in the object dictionary fetched by the master
+        Error::Io(e) => e.raw_os_error() == Some(libc::EINVAL),
+        Error::SdoAbort { code, .. } => *code == SdoAbortCode(0x0609_0011),
+        _ => false,
+    }
+}
+
+fn foe_error(e: Error, slave: SlavePos, result: u32, code: u32) -> Error {
+    match e {
+        Error::Io(ref io) if io.raw_os_error() == Some(libc::EIO) && result != FOE_READY => {
+            Error::Foe {
+                slave,
+                result: FoeResult::from(result),
+                code: FoeErrorCode(code),
+            }
+        }
+        e => e,
+    }
+}
+
+/// The master reports aborted SDO transfers as `EIO` and sets the abort code.
+fn sdo_abort(e: Error, slave: SlavePos, idx: SdoIdx, code: u32) -> Error {
+    match e {
+        Error::Io(ref io) if io.raw_os_error() == Some(libc::EIO) && code != 0 => Error::SdoAbort {
+            slave,
+            idx,
+            code: SdoAbortCode(code),
+        },
+        e => e,
+    }
+}
+
+/// The master reports failed SoE transfers as `EIO` and sets the error code.
+fn soe_error(e: Error, slave: SlavePos, idn: Idn, code: u16) -> Error {
+    match e {
+        Error::Io(ref io) if io.raw_os_error() == Some(libc::EIO) && code != 0 => Error::Soe {
+            slave,
+            idn,
+            code: SoeErrorCode(code),
+        },
+        e => e,
+    }
 }
 
 pub struct SlaveConfig<'m> {
@@ -780,11 +1697,25 @@ impl<'m> SlaveConfig<'m> {
         ioctl!(self.master, ec::ioctl::SC_EMERG_SIZE, &data).map(|_| ())
     }
 
-    pub fn pop_emerg(&mut self, target: &mut [u8]) -> Result<()> {
-        let mut data = ec::ec_ioctl_sc_emerg_t::default();
-        data.config_index = self.idx;
-        data.target = target.as_mut_ptr();
-        ioctl!(self.master, ec::ioctl::SC_EMERG_POP, &mut data).map(|_| ())
+    /// Pop the oldest emergency message from the ring buffer, if any.
+    pub fn pop_emerg(&mut self) -> Result<Option<EmergencyMessage>> {
+        pop_emerg(self.master, self.idx)
+    }
+
+    /// Iterate over the emergency messages, removing them from the ring buffer.
+    ///
+    /// The iterator ends when the ring buffer is empty or after an error.
+    pub fn drain_emerg(&mut self) -> impl Iterator<Item = Result<EmergencyMessage>> + 'm {
+        let (master, idx) = (self.master, self.idx);
+        let mut done = false;
+        std::iter::from_fn(move || {
+            if done {
+                return None;
+            }
+            let next = pop_emerg(master, idx).transpose();
+            done = !matches!(next, Some(Ok(_)));
+            next
+        })
     }
 
     pub fn clear_emerg(&mut self) -> Result<()> {
@@ -800,7 +1731,694 @@ impl<'m> SlaveConfig<'m> {
         Ok(data.overruns)
     }
 
-    // XXX missing: create_sdo_request, create_reg_request, create_voe_handler
+    /// Set the IP parameters that are sent to an EoE slave during configuration.
+    #[cfg(not(feature = "sncn"))]
+    pub fn set_eoe_ip(&mut self, params: &EoeIpParams) -> Result<()> {
+        let mut data = eoe_ip_to_ioctl(params)?;
+        data.config_index = self.idx as u16;
+        ioctl!(self.master, ec::ioctl::SC_EOE_IP_PARAM, &data).map(|_| ())
+    }
+
+    /// Set the timeout of an AL state transition of this slave.
+    ///
+    /// Returns an error if `from` -> `to` is not a valid state transition.
+    #[cfg(not(feature = "sncn"))]
+    pub fn set_state_timeout(
+        &mut self,
+        from: AlState,
+        to: AlState,
+        timeout: Duration,
+    ) -> Result<()> {
+        if !is_valid_al_transition(from, to) {
+            return Err(Error::InvalidAlStateTransition { from, to });
+        }
+        let data = ec::ec_ioctl_sc_state_timeout_t {
+            config_index: self.idx,
+            from_state: u8::from(from) as u32,
+            to_state: u8::from(to) as u32,
+            timeout_ms: u32::try_from(timeout.as_millis()).unwrap_or(u32::MAX),
+        };
+        ioctl!(self.master, ec::ioctl::SC_STATE_TIMEOUT, &data).map(|_| ())
+    }
+
+    /// Set a feature flag that is evaluated by the master during configuration.
+    #[cfg(not(feature = "sncn"))]
+    pub fn set_flag(&mut self, flag: &SlaveConfigFlag, value: i32) -> Result<()> {
+        let mut key = convert::string_to_flag_key(flag.key())?;
+        let data = ec::ec_ioctl_sc_flag_t {
+            config_index: self.idx,
+            key_size: flag.key().len(),
+            key: key.as_mut_ptr(),
+            value,
+        };
+        ioctl!(self.master, ec::ioctl::SC_FLAG, &data).map(|_| ())
+    }
+
+    /// Create an SDO request that can be processed from within the cyclic task.
+    ///
+    /// `size` is the memory reserved for the request, it is grown to fit
+    /// uploaded data.
+    pub fn create_sdo_request(&mut self, sdo_idx: SdoIdx, size: usize) -> Result<SdoRequest> {
+        let mut data = ec::ec_ioctl_sdo_request_t::default();
+        data.config_index = self.idx;
+        data.sdo_index = u16::from(sdo_idx.idx);
+        data.sdo_subindex = u8::from(sdo_idx.sub_idx);
+        data.size = size;
+        ioctl!(self.master, ec::ioctl::SC_SDO_REQUEST, &mut data)?;
+        Ok(SdoRequest {
+            config_idx: self.idx,
+            idx: data.request_index,
+            sdo_idx,
+            mem: vec![0; size],
+            data_size: 0,
+        })
+    }
+
+    /// Create an FoE request that can be processed from within the cyclic task.
+    ///
+    /// `size` is the memory reserved for the request, it is grown to fit
+    /// a received file.
+    #[cfg(feature = "sncn")]
+    pub fn create_foe_request(&mut self, size: usize) -> Result<FoeRequest> {
+        let mut data = ec::ec_ioctl_foe_request_t::default();
+        data.config_index = self.idx;
+        data.size = size as _;
+        ioctl!(self.master, ec::ioctl::SC_FOE_REQUEST, &mut data)?;
+        Ok(FoeRequest {
+            config_idx: self.idx,
+            idx: data.request_index,
+            mem: vec![0; size],
+            data_size: 0,
+            progress: 0,
+            result: FoeResult::Idle,
+            error_code: FoeErrorCode(0),
+        })
+    }
+
+    /// Create an SoE request that can be processed from within the cyclic task.
+    ///
+    /// `size` is the memory reserved for the request, it is grown to fit
+    /// received data.
+    #[cfg(not(feature = "sncn"))]
+    pub fn create_soe_request(
+        &mut self,
+        drive_no: u8,
+        idn: Idn,
+        size: usize,
+    ) -> Result<SoeRequest> {
+        let mut data = ec::ec_ioctl_soe_request_t::default();
+        data.config_index = self.idx;
+        data.drive_no = drive_no;
+        data.idn = u16::from(idn);
+        data.size = size;
+        ioctl!(self.master, ec::ioctl::SC_SOE_REQUEST, &mut data)?;
+        Ok(SoeRequest {
+            config_idx: self.idx,
+            idx: data.request_index,
+            drive_no,
+            idn,
+            mem: vec![0; size],
+            data_size: 0,
+        })
+    }
+
+    /// Create a register request that can be processed from within the cyclic task.
+    ///
+    /// `size` is the maximum number of bytes the request can transfer.
+    pub fn create_reg_request(&mut self, size: usize) -> Result<RegRequest> {
+        let mut data = ec::ec_ioctl_reg_request_t::default();
+        data.config_index = self.idx;
+        data.mem_size = size;
+        ioctl!(self.master, ec::ioctl::SC_REG_REQUEST, &mut data)?;
+        Ok(RegRequest {
+            config_idx: self.idx,
+            idx: data.request_index,
+            mem: vec![0; size],
+            data_size: 0,
+        })
+    }
+
+    /// Create a handler for vendor specific mailbox (VoE) transfers.
+    ///
+    /// `size` is the memory reserved for the handler, it is grown to fit
+    /// received data.
+    pub fn create_voe_handler(&mut self, size: usize) -> Result<VoeHandler> {
+        let mut data = ec::ec_ioctl_voe_t::default();
+        data.config_index = self.idx;
+        data.size = size;
+        ioctl!(self.master, ec::ioctl::SC_VOE, &mut data)?;
+        Ok(VoeHandler {
+            config_idx: self.idx,
+            idx: data.voe_index,
+            mem: vec![0; size],
+            data_size: 0,
+        })
+    }
+}
+
+fn check_request_size(size: usize, mem_size: usize) -> Result<()> {
+    if size > mem_size {
+        return Err(Error::Io(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "{} bytes do not fit into request memory ({} bytes)",
+                size, mem_size
+            ),
+        )));
+    }
+    Ok(())
+}
+
+/// Fetch `size` bytes of received data into `mem` using `fetch`.
+///
+/// The master reallocates the memory of a request to fit an upload, so
+/// `mem` is grown as well if the data is larger than the request was
+/// created with.
+fn fetch_request_data(
+    mem: &mut Vec<u8>,
+    size: usize,
+    fetch: impl FnOnce(*mut u8) -> Result<()>,
+) -> Result<usize> {
+    if size > mem.len() {
+        mem.resize(size, 0);
+    }
+    fetch(mem.as_mut_ptr())?;
+    Ok(size)
+}
+
+/// A non-blocking SDO transfer, processed by the master in the background.
+///
+/// The request does not borrow the master, so that it can be used together
+/// with `Master::send` and `Master::receive` in the cyclic task.
+#[derive(Debug)]
+pub struct SdoRequest {
+    config_idx: SlaveConfigIdx,
+    idx: u32,
+    sdo_idx: SdoIdx,
+    mem: Vec<u8>,
+    data_size: usize,
+}
+
+impl SdoRequest {
+    pub const fn sdo_idx(&self) -> SdoIdx {
+        self.sdo_idx
+    }
+
+    fn ioctl_data(&self) -> ec::ec_ioctl_sdo_request_t {
+        let mut data = ec::ec_ioctl_sdo_request_t::default();
+        data.config_index = self.config_idx;
+        data.request_index = self.idx;
+        data
+    }
+
+    /// Change the SDO that subsequent reads and writes refer to.
+    pub fn set_index(&mut self, master: &Master, sdo_idx: SdoIdx) -> Result<()> {
+        let mut data = self.ioctl_data();
+        data.sdo_index = u16::from(sdo_idx.idx);
+        data.sdo_subindex = u8::from(sdo_idx.sub_idx);
+        ioctl!(master, ec::ioctl::SDO_REQUEST_INDEX, &mut data)?;
+        self.sdo_idx = sdo_idx;
+        Ok(())
+    }
+
+    /// Set the timeout of the request; zero means no timeout.
+    pub fn set_timeout(&mut self, master: &Master, timeout: Duration) -> Result<()> {
+        let mut data = self.ioctl_data();
+        data.timeout = u32::try_from(timeout.as_millis()).unwrap_or(u32::MAX);
+        ioctl!(master, ec::ioctl::SDO_REQUEST_TIMEOUT, &mut data).map(|_| ())
+    }
+
+    /// Get the current state of the request.
+    ///
+    /// When a read has completed, the received data is fetched and
+    /// made available through [`data`](Self::data).
+    pub fn state(&mut self, master: &Master) -> Result<RequestState> {
+        let mut data = self.ioctl_data();
+        ioctl!(master, ec::ioctl::SDO_REQUEST_STATE, &mut data)?;
+        if data.size > 0 {
+            self.data_size = fetch_request_data(&mut self.mem, data.size, |mem| {
+                data.data = mem;
+                ioctl!(master, ec::ioctl::SDO_REQUEST_DATA, &mut data).map(|_| ())
+            })?;
+        }
+        Ok(RequestState::from(data.state))
+    }
+
+    /// Schedule an SDO upload.
+    pub fn read(&mut self, master: &Master) -> Result<()> {
+        let mut data = self.ioctl_data();
+        ioctl!(master, ec::ioctl::SDO_REQUEST_READ, &mut data).map(|_| ())
+    }
+
+    /// Schedule an SDO download of `value`.
+    pub fn write(&mut self, master: &Master, value: &[u8]) -> Result<()> {
+        check_request_size(value.len(), self.mem.len())?;
+        self.mem[..value.len()].copy_from_slice(value);
+        self.data_size = value.len();
+        let mut data = self.ioctl_data();
+        data.data = self.mem.as_mut_ptr();
+        data.size = self.data_size;
+        ioctl!(master, ec::ioctl::SDO_REQUEST_WRITE, &mut data).map(|_| ())
+    }
+
+    /// The data of the last transfer.
+    pub fn data(&self) -> &[u8] {
+        &self.mem[..self.data_size]
+    }
+}
+
+/// A non-blocking SoE transfer, processed by the master in the background.
+///
+/// Like [`SdoRequest`], it does not borrow the master.
+#[cfg(not(feature = "sncn"))]
+#[derive(Debug)]
+pub struct SoeRequest {
+    config_idx: SlaveConfigIdx,
+    idx: u32,
+    drive_no: u8,
+    idn: Idn,
+    mem: Vec<u8>,
+    data_size: usize,
+}
+
+#[cfg(not(feature = "sncn"))]
+impl SoeRequest {
+    pub const fn drive_no(&self) -> u8 {
+        self.drive_no
+    }
+
+    pub const fn idn(&self) -> Idn {
+        self.idn
+    }
+
+    fn ioctl_data(&self) -> ec::ec_ioctl_soe_request_t {
+        let mut data = ec::ec_ioctl_soe_request_t::default();
+        data.config_index = self.config_idx;
+        data.request_index = self.idx;
+        data
+    }
+
+    /// Change the drive and IDN that subsequent reads and writes refer to.
+    pub fn set_idn(&mut self, master: &Master, drive_no: u8, idn: Idn) -> Result<()> {
+        let mut data = self.ioctl_data();
+        data.drive_no = drive_no;
+        data.idn = u16::from(idn);
+        ioctl!(master, ec::ioctl::SOE_REQUEST_IDN, &mut data)?;
+        self.drive_no = drive_no;
+        self.idn = idn;
+        Ok(())
+    }
+
+    /// Set the timeout of the request; zero means no timeout.
+    pub fn set_timeout(&mut self, master: &Master, timeout: Duration) -> Result<()> {
+        let mut data = self.ioctl_data();
+        data.timeout = u32::try_from(timeout.as_millis()).unwrap_or(u32::MAX);
+        ioctl!(master, ec::ioctl::SOE_REQUEST_TIMEOUT, &mut data).map(|_| ())
+    }
+
+    /// Get the current state of the request.
+    ///
+    /// When a read has completed, the received data is fetched and
+    /// made available through [`data`](Self::data).
+    pub fn state(&mut self, master: &Master) -> Result<RequestState> {
+        let mut data = self.ioctl_data();
+        ioctl!(master, ec::ioctl::SOE_REQUEST_STATE, &mut data)?;
+        if data.size > 0 {
+            self.data_size = fetch_request_data(&mut self.mem, data.size, |mem| {
+                data.data = mem;
+                ioctl!(master, ec::ioctl::SOE_REQUEST_DATA, &mut data).map(|_| ())
+            })?;
+        }
+        Ok(RequestState::from(data.state))
+    }
+
+    /// Schedule reading the IDN.
+    pub fn read(&mut self, master: &Master) -> Result<()> {
+        let mut data = self.ioctl_data();
+        ioctl!(master, ec::ioctl::SOE_REQUEST_READ, &mut data).map(|_| ())
+    }
+
+    /// Schedule writing `value` to the IDN.
+    pub fn write(&mut self, master: &Master, value: &[u8]) -> Result<()> {
+        check_request_size(value.len(), self.mem.len())?;
+        self.mem[..value.len()].copy_from_slice(value);
+        self.data_size = value.len();
+        let mut data = self.ioctl_data();
+        data.data = self.mem.as_mut_ptr();
+        data.size = self.data_size;
+        ioctl!(master, ec::ioctl::SOE_REQUEST_WRITE, &mut data).map(|_| ())
+    }
+
+    /// The data of the last transfer.
+    pub fn data(&self) -> &[u8] {
+        &self.mem[..self.data_size]
+    }
+}
+
+/// A non-blocking access to the ESC registers of a configured slave.
+///
+/// Like [`SdoRequest`], it does not borrow the master.
+#[derive(Debug)]
+pub struct RegRequest {
+    config_idx: SlaveConfigIdx,
+    idx: u32,
+    mem: Vec<u8>,
+    data_size: usize,
+}
+
+impl RegRequest {
+    fn ioctl_data(&self) -> ec::ec_ioctl_reg_request_t {
+        let mut data = ec::ec_ioctl_reg_request_t::default();
+        data.config_index = self.config_idx;
+        data.request_index = self.idx;
+        data
+    }
+
+    /// Get the current state of the request.
+    ///
+    /// When a read has completed, the register contents are fetched and
+    /// made available through [`data`](Self::data).
+    pub fn state(&mut self, master: &Master) -> Result<RequestState> {
+        let mut data = self.ioctl_data();
+        ioctl!(master, ec::ioctl::REG_REQUEST_STATE, &mut data)?;
+        if data.new_data != 0 {
+            data.data = self.mem.as_mut_ptr();
+            data.mem_size = self.mem.len();
+            ioctl!(master, ec::ioctl::REG_REQUEST_DATA, &mut data)?;
+        }
+        Ok(RequestState::from(data.state))
+    }
+
+    /// Schedule reading `size` bytes starting at register `address`.
+    pub fn read(&mut self, master: &Master, address: u16, size: usize) -> Result<()> {
+        check_request_size(size, self.mem.len())?;
+        let mut data = self.ioctl_data();
+        data.address = address;
+        data.transfer_size = size;
+        ioctl!(master, ec::ioctl::REG_REQUEST_READ, &mut data)?;
+        self.data_size = size;
+        Ok(())
+    }
+
+    /// Schedule writing `value` to the registers starting at `address`.
+    pub fn write(&mut self, master: &Master, address: u16, value: &[u8]) -> Result<()> {
+        check_request_size(value.len(), self.mem.len())?;
+        self.mem[..value.len()].copy_from_slice(value);
+        let mut data = self.ioctl_data();
+        data.data = self.mem.as_mut_ptr();
+        data.address = address;
+        data.transfer_size = value.len();
+        ioctl!(master, ec::ioctl::REG_REQUEST_WRITE, &mut data)?;
+        self.data_size = value.len();
+        Ok(())
+    }
+
+    /// The data of the last transfer.
+    pub fn data(&self) -> &[u8] {
+        &self.mem[..self.data_size]
+    }
+}
+
+/// A handler for vendor specific mailbox (VoE) transfers.
+///
+/// Transfers are started with [`read`](Self::read) or [`write`](Self::write)
+/// and then driven by calling [`execute`](Self::execute) in every cycle
+/// until it does not return [`RequestState::Busy`] anymore.
+#[derive(Debug)]
+pub struct VoeHandler {
+    config_idx: SlaveConfigIdx,
+    idx: u32,
+    mem: Vec<u8>,
+    data_size: usize,
+}
+
+impl VoeHandler {
+    fn ioctl_data(&self) -> ec::ec_ioctl_voe_t {
+        let mut data = ec::ec_ioctl_voe_t::default();
+        data.config_index = self.config_idx;
+        data.voe_index = self.idx;
+        data
+    }
+
+    /// Set the VoE header for future send operations.
+    pub fn set_send_header(&mut self, master: &Master, header: VoeHeader) -> Result<()> {
+        let mut vendor_id = header.vendor_id;
+        let mut vendor_type = header.vendor_type;
+        let mut data = self.ioctl_data();
+        data.vendor_id = &mut vendor_id;
+        data.vendor_type = &mut vendor_type;
+        ioctl!(master, ec::ioctl::VOE_SEND_HEADER, &data).map(|_| ())
+    }
+
+    /// Get the VoE header of the last received mailbox message.
+    pub fn received_header(&self, master: &Master) -> Result<VoeHeader> {
+        let mut header = VoeHeader::default();
+        let mut data = self.ioctl_data();
+        data.vendor_id = &mut header.vendor_id;
+        data.vendor_type = &mut header.vendor_type;
+        ioctl!(master, ec::ioctl::VOE_REC_HEADER, &mut data)?;
+        Ok(header)
+    }
+
+    /// Start a VoE read operation.
+    ///
+    /// The slave is first asked whether it has data to send.
+    pub fn read(&mut self, master: &Master) -> Result<()> {
+        let data = self.ioctl_data();
+        ioctl!(master, ec::ioctl::VOE_READ, &data).map(|_| ())
+    }
+
+    /// Start a VoE read operation without querying the mailbox state first.
+    pub fn read_nosync(&mut self, master: &Master) -> Result<()> {
+        let data = self.ioctl_data();
+        ioctl!(master, ec::ioctl::VOE_READ_NOSYNC, &data).map(|_| ())
+    }
+
+    /// Start a VoE write operation sending `value`.
+    pub fn write(&mut self, master: &Master, value: &[u8]) -> Result<()> {
+        check_request_size(value.len(), self.mem.len())?;
+        self.mem[..value.len()].copy_from_slice(value);
+        let mut data = self.ioctl_data();
+        data.data = self.mem.as_mut_ptr();
+        data.size = value.len();
+        ioctl!(master, ec::ioctl::VOE_WRITE, &mut data)?;
+        self.data_size = value.len();
+        Ok(())
+    }
+
+    /// Execute the handler; this has to be called cyclically while a
+    /// transfer is in progress.
+    ///
+    /// When a read has completed, the received data is fetched and
+    /// made available through [`data`](Self::data).
+    pub fn execute(&mut self, master: &Master) -> Result<RequestState> {
+        let mut data = self.ioctl_data();
+        ioctl!(master, ec::ioctl::VOE_EXEC, &mut data)?;
+        if data.size > 0 {
+            self.data_size = fetch_request_data(&mut self.mem, data.size, |mem| {
+                data.data = mem;
+                ioctl!(master, ec::ioctl::VOE_DATA, &mut data).map(|_| ())
+            })?;
+        }
+        Ok(RequestState::from(data.state))
+    }
+
+    /// The data of the last transfer.
+    pub fn data(&self) -> &[u8] {
+        &self.mem[..self.data_size]
+    }
+}
+
+/// A non-blocking FoE transfer, processed by the master in the background.
+///
+/// Like [`SdoRequest`], the request does not borrow the master.
+#[cfg(feature = "sncn")]
+#[derive(Debug)]
+pub struct FoeRequest {
+    config_idx: SlaveConfigIdx,
+    idx: u32,
+    mem: Vec<u8>,
+    data_size: usize,
+    progress: usize,
+    result: FoeResult,
+    error_code: FoeErrorCode,
+}
+
+#[cfg(feature = "sncn")]
+impl FoeRequest {
+    fn ioctl_data(&self) -> ec::ec_ioctl_foe_request_t {
+        let mut data = ec::ec_ioctl_foe_request_t::default();
+        data.config_index = self.config_idx;
+        data.request_index = self.idx;
+        data
+    }
+
+    /// Select the file that subsequent reads and writes refer to.
+    pub fn set_file(&mut self, master: &Master, name: &str, password: u32) -> Result<()> {
+        let mut data = self.ioctl_data();
+        data.file_name = convert::string_to_foe_name(name)?;
+        data.password = password;
+        ioctl!(master, ec::ioctl::FOE_REQUEST_FILE, &mut data).map(|_| ())
+    }
+
+    /// Set the timeout of the request; zero means no timeout.
+    pub fn set_timeout(&mut self, master: &Master, timeout: Duration) -> Result<()> {
+        let mut data = self.ioctl_data();
+        data.timeout = u32::try_from(timeout.as_millis()).unwrap_or(u32::MAX);
+        ioctl!(master, ec::ioctl::FOE_REQUEST_TIMEOUT, &mut data).map(|_| ())
+    }
+
+    /// Get the current state of the request.
+    ///
+    /// When a read has completed, the received file is fetched and
+    /// made available through [`data`](Self::data).
+    pub fn state(&mut self, master: &Master) -> Result<RequestState> {
+        let mut data = self.ioctl_data();
+        ioctl!(master, ec::ioctl::FOE_REQUEST_STATE, &mut data)?;
+        let state = RequestState::from(data.state);
+        self.progress = data.progress as _;
+        self.result = FoeResult::from(data.result);
+        self.error_code = FoeErrorCode(data.error_code);
+        let size: usize = data.size as _;
+        if state == RequestState::Success && size > 0 {
+            self.data_size = fetch_request_data(&mut self.mem, size, |mem| {
+                data.data = mem;
+                ioctl!(master, ec::ioctl::FOE_REQUEST_DATA, &mut data).map(|_| ())
+            })?;
+        }
+        Ok(state)
+    }
+
+    /// Schedule reading the file from the slave.
+    pub fn read(&mut self, master: &Master) -> Result<()> {
+        let mut data = self.ioctl_data();
+        ioctl!(master, ec::ioctl::FOE_REQUEST_READ, &mut data).map(|_| ())
+    }
+
+    /// Schedule writing `file` to the slave.
+    pub fn write(&mut self, master: &Master, file: &[u8]) -> Result<()> {
+        check_request_size(file.len(), self.mem.len())?;
+        self.mem[..file.len()].copy_from_slice(file);
+        self.data_size = file.len();
+        let mut data = self.ioctl_data();
+        data.data = self.mem.as_mut_ptr();
+        data.size = self.data_size as _;
+        ioctl!(master, ec::ioctl::FOE_REQUEST_WRITE, &mut data).map(|_| ())
+    }
+
+    /// Wait until the transfer has finished, passing the number of bytes
+    /// transferred so far to `progress`.
+    ///
+    /// The master processes the request while frames are exchanged, so
+    /// `cycle` is called before each poll to run one cycle of the
+    /// application, e.g. `Master::receive`, processing the domains,
+    /// `Master::send` and sleeping until the next period.
+    pub fn wait<C, P>(&mut self, master: &mut Master, mut cycle: C, mut progress: P) -> Result<()>
+    where
+        C: FnMut(&mut Master) -> Result<()>,
+        P: FnMut(usize),
+    {
+        loop {
+            cycle(master)?;
+            let state = self.state(master)?;
+            progress(self.progress);
+            match state {
+                RequestState::Success => return Ok(()),
+                RequestState::Error => return Err(self.transfer_error(master)),
+                RequestState::Unused | RequestState::Busy => {}
+            }
+        }
+    }
+
+    /// Read the file from the slave into `writer`, returning its size.
+    ///
+    /// The file can be as large as the request memory; see [`wait`](Self::wait)
+    /// for `cycle` and `progress`.
+    pub fn read_to<W, C, P>(
+        &mut self,
+        master: &mut Master,
+        writer: &mut W,
+        cycle: C,
+        progress: P,
+    ) -> Result<u64>
+    where
+        W: io::Write,
+        C: FnMut(&mut Master) -> Result<()>,
+        P: FnMut(usize),
+    {
+        self.read(master)?;
+        self.wait(master, cycle, progress)?;
+        writer.write_all(self.data())?;
+        Ok(self.data_size as u64)
+    }
+
+    /// Write the file read from `reader` to the slave.
+    ///
+    /// The file can be as large as the request memory; see [`wait`](Self::wait)
+    /// for `cycle` and `progress`.
+    pub fn write_from<R, C, P>(
+        &mut self,
+        master: &mut Master,
+        reader: &mut R,
+        cycle: C,
+        progress: P,
+    ) -> Result<()>
+    where
+        R: io::Read,
+        C: FnMut(&mut Master) -> Result<()>,
+        P: FnMut(usize),
+    {
+        use std::io::Read;
+
+        let mut file = Vec::new();
+        // one byte more, so that a file too large for the request is detected
+        reader
+            .take(self.mem.len() as u64 + 1)
+            .read_to_end(&mut file)?;
+        self.write(master, &file)?;
+        self.wait(master, cycle, progress)
+    }
+
+    fn transfer_error(&self, master: &Master) -> Error {
+        let slave = master
+            .get_config_info(self.config_idx)
+            .ok()
+            .and_then(|info| info.slave_position);
+        match slave {
+            Some(slave) => Error::Foe {
+                slave,
+                result: self.result,
+                code: self.error_code,
+            },
+            None => Error::Io(io::Error::new(
+                io::ErrorKind::Other,
+                format!(
+                    "FoE transfer failed: {:?} (code {})",
+                    self.result, self.error_code
+                ),
+            )),
+        }
+    }
+
+    /// Number of bytes transferred so far, as of the last [`state`](Self::state).
+    pub const fn progress(&self) -> usize {
+        self.progress
+    }
+
+    /// Result of the transfer, as of the last [`state`](Self::state).
+    pub const fn result(&self) -> FoeResult {
+        self.result
+    }
+
+    /// Error code sent by the slave, as of the last [`state`](Self::state).
+    pub const fn error_code(&self) -> FoeErrorCode {
+        self.error_code
+    }
+
+    /// The data of the last transfer.
+    pub fn data(&self) -> &[u8] {
+        &self.mem[..self.data_size]
+    }
 }
 
 impl<'m> Domain<'m> {
@@ -0,0 +1,147 @@
+// Part of ethercat-rs. Copyright 2018-2022 by the authors.
+// This work is dual-licensed under Apache 2.0 and MIT terms.
+
+//! Reading and writing of libpcap capture files.
+//!
+//! EtherCAT frames are Ethernet frames with EtherType 0x88A4, so they are
+//! stored with the Ethernet link type, which Wireshark dissects as EtherCAT.
+
+use crate::types::{Error, Result};
+use std::{io, time::Duration};
+
+/// Link type of Ethernet frames, including EtherCAT frames.
+pub const LINKTYPE_ETHERNET: u32 = 1;
+
+const PCAP_MAGIC: u32 = 0xA1B2_C3D4;
+const PCAP_VERSION: (u16, u16) = (2, 4);
+const PCAP_SNAPLEN: u32 = 65535;
+const GLOBAL_HEADER_SIZE: usize = 24;
+const RECORD_HEADER_SIZE: usize = 16;
+
+/// A captured frame.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PcapRecord {
+    /// Capture time, relative to the UNIX epoch or the start of the master
+    pub timestamp: Duration,
+    /// Length of the frame on the wire, may be larger than `data`
+    pub orig_len: u32,
+    pub data: Vec<u8>,
+}
+
+/// Parse the records of a libpcap file with microsecond timestamps.
+pub fn parse_pcap(data: &[u8]) -> Result<Vec<PcapRecord>> {
+    let invalid = |msg: &str| Error::Io(io::Error::new(io::ErrorKind::InvalidData, msg));
+    let magic = data
+        .get(..4)
+        .map(|m| [m[0], m[1], m[2], m[3]])
+        .ok_or_else(|| invalid("pcap data is too short"))?;
+    let read_u32: fn([u8; 4]) -> u32 = if u32::from_le_bytes(magic) == PCAP_MAGIC {
+        u32::from_le_bytes
+    } else if u32::from_be_bytes(magic) == PCAP_MAGIC {
+        u32::from_be_bytes
+    } else {
+        return Err(invalid("invalid pcap magic"));
+    };
+    let field = |pos: usize| read_u32([data[pos], data[pos + 1], data[pos + 2], data[pos + 3]]);
+    if data.len() < GLOBAL_HEADER_SIZE {
+        return Err(invalid("pcap data is too short"));
+    }
+    let mut records = vec![];
+    let mut pos = GLOBAL_HEADER_SIZE;
+    while pos + RECORD_HEADER_SIZE <= data.len() {
+        let ts_sec = field(pos);
+        let ts_usec = field(pos + 4);
+        let incl_len = field(pos + 8) as usize;
+        let orig_len = field(pos + 12);
+        let start = pos + RECORD_HEADER_SIZE;
+        let frame = data
+            .get(start..start + incl_len)
+            .ok_or_else(|| invalid("truncated pcap record"))?;
+        records.push(PcapRecord {
+            timestamp: Duration::new(u64::from(ts_sec), ts_usec.saturating_mul(1000)),
+            orig_len,
+            data: frame.to_vec(),
+        });
+        pos = start + incl_len;
+    }
+    Ok(records)
+}
+
+/// Writer for libpcap files with microsecond timestamps.
+pub struct PcapWriter<W: io::Write> {
+    inner: W,
+}
+
+impl<W: io::Write> PcapWriter<W> {
+    /// Create a writer for Ethernet (EtherCAT) frames and write the file header.
+    pub fn new(inner: W) -> Result<Self> {
+        Self::with_link_type(inner, LINKTYPE_ETHERNET)
+    }
+
+    pub fn with_link_type(mut inner: W, link_type: u32) -> Result<Self> {
+        let mut header = Vec::with_capacity(GLOBAL_HEADER_SIZE);
+        header.extend_from_slice(&PCAP_MAGIC.to_le_bytes());
+        header.extend_from_slice(&PCAP_VERSION.0.to_le_bytes());
+        header.extend_from_slice(&PCAP_VERSION.1.to_le_bytes());
+        header.extend_from_slice(&0_i32.to_le_bytes()); // thiszone
+        header.extend_from_slice(&0_u32.to_le_bytes()); // sigfigs
+        header.extend_from_slice(&PCAP_SNAPLEN.to_le_bytes());
+        header.extend_from_slice(&link_type.to_le_bytes());
+        inner.write_all(&header)?;
+        Ok(Self { inner })
+    }
+
+    pub fn write_record(&mut self, record: &PcapRecord) -> Result<()> {
+        let mut header = Vec::with_capacity(RECORD_HEADER_SIZE);
+        header.extend_from_slice(&(record.timestamp.as_secs() as u32).to_le_bytes());
+        header.extend_from_slice(&record.timestamp.subsec_micros().to_le_bytes());
+        header.extend_from_slice(&(record.data.len() as u32).to_le_bytes());
+        header.extend_from_slice(&record.orig_len.to_le_bytes());
+        self.inner.write_all(&header)?;
+        self.inner.write_all(&record.data)?;
+        Ok(())
+    }
+
+    pub fn write_frame(&mut self, timestamp: Duration, frame: &[u8]) -> Result<()> {
+        self.write_record(&PcapRecord {
+            timestamp,
+            orig_len: frame.len() as u32,
+            data: frame.to_vec(),
+        })
+    }
+
+    pub fn flush(&mut self) -> Result<()> {
+        Ok(self.inner.flush()?)
+    }
+
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+#[test]
+fn test_pcap_roundtrip() {
+    let record = PcapRecord {
+        timestamp: Duration::new(1_600_000_000, 123_000),
+        orig_len: 60,
+        data: vec![0xFF; 60],
+    };
+    let mut writer = PcapWriter::new(Vec::new()).unwrap();
+    writer.write_record(&record).unwrap();
+    writer
+        .write_frame(Duration::from_millis(1500), &[1, 2, 3])
+        .unwrap();
+    let file = writer.into_inner();
+    assert_eq!(file.len(), 24 + 16 + 60 + 16 + 3);
+    assert_eq!(&file[20..24], &LINKTYPE_ETHERNET.to_le_bytes());
+
+    let records = parse_pcap(&file).unwrap();
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0], record);
+    assert_eq!(records[1].timestamp, Duration::from_millis(1500));
+    assert_eq!(records[1].data, [1, 2, 3]);
+
+    assert!(parse_pcap(&file[..30]).is_ok());
+    assert!(parse_pcap(&file[..50]).is_err());
+    assert!(parse_pcap(&[0; 24]).is_err());
+}
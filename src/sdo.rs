@@ -0,0 +1,505 @@
+// Part of ethercat-rs. Copyright 2018-2022 by the authors.
+// This work is dual-licensed under Apache 2.0 and MIT terms.
+
+//! Decoding and encoding of SDO data according to its CoE data type, and
+//! SDO abort codes.
+
+use crate::types::{DataType, Error, Result, SubIdx, Value};
+use std::{convert::TryFrom, fmt};
+
+/// Decode raw SDO data of the given data type.
+///
+/// Data types without a matching [`Value`] variant are returned as
+/// [`Value::Raw`]. BIT2 to BIT8 are returned as [`Value::U8`], since the
+/// `BitN` variants only hold a single bit. Visible strings are cut at the
+/// first NUL byte.
+pub fn decode_sdo_value(data_type: DataType, data: &[u8]) -> Result<Value> {
+    if let Some(mask) = bit_mask(data_type) {
+        return Ok(Value::U8(le::<1>(data)?[0] & mask));
+    }
+    Ok(match data_type {
+        DataType::Bool => Value::Bool(le::<1>(data)?[0] & 1 != 0),
+        DataType::Bit1 => Value::Bit1(le::<1>(data)?[0] != 0),
+        DataType::Byte => Value::Byte(le::<1>(data)?[0]),
+        DataType::I8 => Value::I8(i8::from_le_bytes(le(data)?)),
+        DataType::I16 => Value::I16(i16::from_le_bytes(le(data)?)),
+        DataType::I32 => Value::I32(i32::from_le_bytes(le(data)?)),
+        DataType::I64 => Value::I64(i64::from_le_bytes(le(data)?)),
+        DataType::U8 => Value::U8(le::<1>(data)?[0]),
+        DataType::U16 => Value::U16(u16::from_le_bytes(le(data)?)),
+        DataType::U32 => Value::U32(u32::from_le_bytes(le(data)?)),
+        DataType::U64 => Value::U64(u64::from_le_bytes(le(data)?)),
+        DataType::F32 => Value::F32(f32::from_le_bytes(le(data)?)),
+        DataType::F64 => Value::F64(f64::from_le_bytes(le(data)?)),
+        DataType::String => {
+            let end = data.iter().position(|&b| b == 0).unwrap_or(data.len());
+            Value::String(String::from_utf8_lossy(&data[..end]).into_owned())
+        }
+        DataType::U8Array => Value::U8Array(data.to_vec()),
+        DataType::U16Array => {
+            if data.len() % 2 != 0 {
+                return Err(Error::InvalidSdoData(format!(
+                    "odd length {} of unicode string",
+                    data.len()
+                )));
+            }
+            Value::U16Array(
+                data.chunks_exact(2)
+                    .map(|c| u16::from_le_bytes([c[0], c[1]]))
+                    .collect(),
+            )
+        }
+        _ => Value::Raw(data.to_vec()),
+    })
+}
+
+/// Encode a value into raw SDO data.
+pub fn encode_sdo_value(value: &Value) -> Vec<u8> {
+    match value {
+        Value::Bool(v)
+        | Value::Bit1(v)
+        | Value::Bit2(v)
+        | Value::Bit3(v)
+        | Value::Bit4(v)
+        | Value::Bit5(v)
+        | Value::Bit6(v)
+        | Value::Bit7(v)
+        | Value::Bit8(v) => vec![u8::from(*v)],
+        Value::Byte(v) | Value::U8(v) => vec![*v],
+        Value::I8(v) => v.to_le_bytes().to_vec(),
+        Value::I16(v) => v.to_le_bytes().to_vec(),
+        Value::I32(v) => v.to_le_bytes().to_vec(),
+        Value::I64(v) => v.to_le_bytes().to_vec(),
+        Value::U16(v) => v.to_le_bytes().to_vec(),
+        Value::U32(v) => v.to_le_bytes().to_vec(),
+        Value::U64(v) => v.to_le_bytes().to_vec(),
+        Value::F32(v) => v.to_le_bytes().to_vec(),
+        Value::F64(v) => v.to_le_bytes().to_vec(),
+        Value::String(v) => v.as_bytes().to_vec(),
+        Value::U8Array(v) | Value::Raw(v) => v.clone(),
+        Value::U16Array(v) => v.iter().flat_map(|w| w.to_le_bytes()).collect(),
+    }
+}
+
+/// Decode the data of a complete access upload starting with subindex 0.
+///
+/// `entries` are the subindex, data type and bit length of the entries
+/// present in the object. Subindex 0 is transferred padded to 16 bits, the
+/// entries follow packed by their bit length.
+#[cfg_attr(not(feature = "sncn"), allow(dead_code))]
+pub(crate) fn decode_complete_access(
+    entries: &[(SubIdx, DataType, u16)],
+    data: &[u8],
+) -> Result<Vec<(SubIdx, Value)>> {
+    let mut bit_pos = 16;
+    entries
+        .iter()
+        .map(|&(sub, data_type, bit_len)| {
+            let bit_len = usize::from(bit_len);
+            let start = bit_pos / 8;
+            let end = (bit_pos + bit_len + 7) / 8;
+            let bytes = data.get(start..end).ok_or_else(|| {
+                Error::InvalidSdoData(format!(
+                    "complete access data of {} bytes ends before subindex {}",
+                    data.len(),
+                    u8::from(sub)
+                ))
+            })?;
+            let value = if bit_pos % 8 == 0 && bit_len % 8 == 0 {
+                decode_sdo_value(data_type, bytes)?
+            } else if bit_len <= 8 {
+                let word = bytes
+                    .iter()
+                    .rev()
+                    .fold(0_u16, |word, b| (word << 8) | u16::from(*b));
+                let bits = (word >> (bit_pos % 8)) as u8 & (0xFF >> (8 - bit_len));
+                decode_sdo_value(data_type, &[bits])?
+            } else {
+                return Err(Error::InvalidSdoData(format!(
+                    "subindex {} of {} bits is not byte aligned",
+                    u8::from(sub),
+                    bit_len
+                )));
+            };
+            bit_pos += bit_len;
+            Ok((sub, value))
+        })
+        .collect()
+}
+
+/// Mask of the value bits of the BIT2 to BIT8 data types.
+const fn bit_mask(data_type: DataType) -> Option<u8> {
+    Some(match data_type {
+        DataType::Bit2 => 0x03,
+        DataType::Bit3 => 0x07,
+        DataType::Bit4 => 0x0F,
+        DataType::Bit5 => 0x1F,
+        DataType::Bit6 => 0x3F,
+        DataType::Bit7 => 0x7F,
+        DataType::Bit8 => 0xFF,
+        _ => return None,
+    })
+}
+
+fn le<const N: usize>(data: &[u8]) -> Result<[u8; N]> {
+    data.get(..N)
+        .and_then(|d| <[u8; N]>::try_from(d).ok())
+        .ok_or_else(|| Error::InvalidSdoData(format!("expected {} bytes, got {}", N, data.len())))
+}
+
+/// The data type a value is encoded as, `None` for raw data.
+const fn value_data_type(value: &Value) -> Option<DataType> {
+    Some(match value {
+        Value::Bool(_) => DataType::Bool,
+        Value::Byte(_) => DataType::Byte,
+        Value::I8(_) => DataType::I8,
+        Value::I16(_) => DataType::I16,
+        Value::I32(_) => DataType::I32,
+        Value::I64(_) => DataType::I64,
+        Value::U8(_) => DataType::U8,
+        Value::U16(_) => DataType::U16,
+        Value::U32(_) => DataType::U32,
+        Value::U64(_) => DataType::U64,
+        Value::F32(_) => DataType::F32,
+        Value::F64(_) => DataType::F64,
+        Value::String(_) => DataType::String,
+        Value::U8Array(_) => DataType::U8Array,
+        Value::U16Array(_) => DataType::U16Array,
+        Value::Bit1(_) => DataType::Bit1,
+        Value::Bit2(_) => DataType::Bit2,
+        Value::Bit3(_) => DataType::Bit3,
+        Value::Bit4(_) => DataType::Bit4,
+        Value::Bit5(_) => DataType::Bit5,
+        Value::Bit6(_) => DataType::Bit6,
+        Value::Bit7(_) => DataType::Bit7,
+        Value::Bit8(_) => DataType::Bit8,
+        Value::Raw(_) => return None,
+    })
+}
+
+/// Types that can be read from and written to SDO entries with
+/// [`Master::sdo_read`](crate::Master::sdo_read) and
+/// [`Master::sdo_write`](crate::Master::sdo_write).
+///
+/// Integers convert from and to every integer and bit data type as long as
+/// the value fits; `Vec<u8>` reads and writes the raw bytes of any entry.
+pub trait SdoValue: Sized {
+    /// Convert a decoded value, `None` if it cannot be represented.
+    fn from_value(value: Value) -> Option<Self>;
+    /// Convert into a value of the given data type, `None` if impossible.
+    fn to_value(&self, data_type: DataType) -> Option<Value>;
+}
+
+impl SdoValue for Value {
+    fn from_value(value: Value) -> Option<Self> {
+        Some(value)
+    }
+
+    fn to_value(&self, data_type: DataType) -> Option<Value> {
+        match (self, bit_mask(data_type)) {
+            // BIT2 to BIT8 are decoded as U8
+            (Value::U8(v), Some(mask)) => Some(Value::U8(v & mask)),
+            _ => match value_data_type(self) {
+                Some(dt) if dt != data_type => None,
+                _ => Some(self.clone()),
+            },
+        }
+    }
+}
+
+impl SdoValue for bool {
+    fn from_value(value: Value) -> Option<Self> {
+        match value {
+            Value::Bool(v)
+            | Value::Bit1(v)
+            | Value::Bit2(v)
+            | Value::Bit3(v)
+            | Value::Bit4(v)
+            | Value::Bit5(v)
+            | Value::Bit6(v)
+            | Value::Bit7(v)
+            | Value::Bit8(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    fn to_value(&self, data_type: DataType) -> Option<Value> {
+        let v = *self;
+        Some(match data_type {
+            DataType::Bool => Value::Bool(v),
+            DataType::Bit1 => Value::Bit1(v),
+            DataType::Bit2 => Value::Bit2(v),
+            DataType::Bit3 => Value::Bit3(v),
+            DataType::Bit4 => Value::Bit4(v),
+            DataType::Bit5 => Value::Bit5(v),
+            DataType::Bit6 => Value::Bit6(v),
+            DataType::Bit7 => Value::Bit7(v),
+            DataType::Bit8 => Value::Bit8(v),
+            _ => return None,
+        })
+    }
+}
+
+const fn int_of(value: &Value) -> Option<i128> {
+    Some(match *value {
+        Value::Byte(v) | Value::U8(v) => v as i128,
+        Value::U16(v) => v as i128,
+        Value::U32(v) => v as i128,
+        Value::U64(v) => v as i128,
+        Value::I8(v) => v as i128,
+        Value::I16(v) => v as i128,
+        Value::I32(v) => v as i128,
+        Value::I64(v) => v as i128,
+        Value::Bit1(v)
+        | Value::Bit2(v)
+        | Value::Bit3(v)
+        | Value::Bit4(v)
+        | Value::Bit5(v)
+        | Value::Bit6(v)
+        | Value::Bit7(v)
+        | Value::Bit8(v) => v as i128,
+        _ => return None,
+    })
+}
+
+fn int_value(data_type: DataType, v: i128) -> Option<Value> {
+    if let Some(mask) = bit_mask(data_type) {
+        return u8::try_from(v)
+            .ok()
+            .filter(|v| v & !mask == 0)
+            .map(Value::U8);
+    }
+    Some(match data_type {
+        DataType::Bit1 => Value::Bit1(match v {
+            0 => false,
+            1 => true,
+            _ => return None,
+        }),
+        DataType::Byte => Value::Byte(u8::try_from(v).ok()?),
+        DataType::U8 => Value::U8(u8::try_from(v).ok()?),
+        DataType::U16 => Value::U16(u16::try_from(v).ok()?),
+        DataType::U32 => Value::U32(u32::try_from(v).ok()?),
+        DataType::U64 => Value::U64(u64::try_from(v).ok()?),
+        DataType::I8 => Value::I8(i8::try_from(v).ok()?),
+        DataType::I16 => Value::I16(i16::try_from(v).ok()?),
+        DataType::I32 => Value::I32(i32::try_from(v).ok()?),
+        DataType::I64 => Value::I64(i64::try_from(v).ok()?),
+        _ => return None,
+    })
+}
+
+macro_rules! impl_sdo_value_int {
+    ($($t:ty),*) => {
+        $(
+            impl SdoValue for $t {
+                fn from_value(value: Value) -> Option<Self> {
+                    int_of(&value).and_then(|v| Self::try_from(v).ok())
+                }
+
+                fn to_value(&self, data_type: DataType) -> Option<Value> {
+                    int_value(data_type, i128::from(*self))
+                }
+            }
+        )*
+    };
+}
+
+impl_sdo_value_int!(u8, u16, u32, u64, i8, i16, i32, i64);
+
+impl SdoValue for f32 {
+    fn from_value(value: Value) -> Option<Self> {
+        match value {
+            Value::F32(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    fn to_value(&self, data_type: DataType) -> Option<Value> {
+        (data_type == DataType::F32).then_some(Value::F32(*self))
+    }
+}
+
+impl SdoValue for f64 {
+    fn from_value(value: Value) -> Option<Self> {
+        match value {
+            Value::F32(v) => Some(f64::from(v)),
+            Value::F64(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    fn to_value(&self, data_type: DataType) -> Option<Value> {
+        (data_type == DataType::F64).then_some(Value::F64(*self))
+    }
+}
+
+impl SdoValue for String {
+    fn from_value(value: Value) -> Option<Self> {
+        match value {
+            Value::String(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    fn to_value(&self, data_type: DataType) -> Option<Value> {
+        (data_type == DataType::String).then(|| Value::String(self.clone()))
+    }
+}
+
+impl SdoValue for Vec<u8> {
+    fn from_value(value: Value) -> Option<Self> {
+        Some(match value {
+            Value::U8Array(v) | Value::Raw(v) => v,
+            v => encode_sdo_value(&v),
+        })
+    }
+
+    fn to_value(&self, data_type: DataType) -> Option<Value> {
+        Some(match data_type {
+            DataType::U8Array => Value::U8Array(self.clone()),
+            _ => Value::Raw(self.clone()),
+        })
+    }
+}
+
+/// SDO abort code as defined by CiA 301 and ETG.1000.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SdoAbortCode(pub u32);
+
+impl SdoAbortCode {
+    /// Human-readable description of a known abort code.
+    pub fn description(self) -> Option<&'static str> {
+        Some(match self.0 {
+            0x0503_0000 => "Toggle bit not changed",
+            0x0504_0000 => "SDO protocol timeout",
+            0x0504_0001 => "Client/server command specifier not valid or unknown",
+            0x0504_0002 => "Invalid block size",
+            0x0504_0003 => "Invalid sequence number",
+            0x0504_0004 => "CRC error",
+            0x0504_0005 => "Out of memory",
+            0x0601_0000 => "Unsupported access to an object",
+            0x0601_0001 => "Attempt to read a write-only object",
+            0x0601_0002 => "Attempt to write a read-only object",
+            0x0601_0003 => "Subindex cannot be written, SI0 must be 0 for write access",
+            0x0601_0004 => "Complete access not supported for objects of variable length",
+            0x0601_0005 => "Object length exceeds mailbox size",
+            0x0601_0006 => "Object mapped to RxPDO, SDO download blocked",
+            0x0602_0000 => "Object does not exist in the object dictionary",
+            0x0604_0041 => "Object cannot be mapped to the PDO",
+            0x0604_0042 => "Number and length of the objects to be mapped would exceed PDO length",
+            0x0604_0043 => "General parameter incompatibility",
+            0x0604_0047 => "General internal incompatibility in the device",
+            0x0606_0000 => "Access failed due to a hardware error",
+            0x0607_0010 => "Data type does not match, length of service parameter does not match",
+            0x0607_0012 => "Data type does not match, length of service parameter too high",
+            0x0607_0013 => "Data type does not match, length of service parameter too low",
+            0x0609_0011 => "Subindex does not exist",
+            0x0609_0030 => "Value range of parameter exceeded",
+            0x0609_0031 => "Value of parameter written too high",
+            0x0609_0032 => "Value of parameter written too low",
+            0x0609_0036 => "Maximum value is less than minimum value",
+            0x060A_0023 => "Resource not available: SDO connection",
+            0x0800_0000 => "General error",
+            0x0800_0020 => "Data cannot be transferred or stored to the application",
+            0x0800_0021 => {
+                "Data cannot be transferred or stored to the application because of local control"
+            }
+            0x0800_0022 => {
+                "Data cannot be transferred or stored to the application because of the present device state"
+            }
+            0x0800_0023 => "Object dictionary dynamic generation failed or no object dictionary present",
+            0x0800_0024 => "No data available",
+            _ => return None,
+        })
+    }
+}
+
+impl fmt::Display for SdoAbortCode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.description() {
+            Some(d) => write!(f, "0x{:08X} ({})", self.0, d),
+            None => write!(f, "0x{:08X} (unknown abort code)", self.0),
+        }
+    }
+}
+
+#[test]
+fn test_decode_sdo_value() {
+    assert_eq!(
+        decode_sdo_value(DataType::U16, &[0x34, 0x12]).unwrap(),
+        Value::U16(0x1234)
+    );
+    assert_eq!(
+        decode_sdo_value(DataType::I32, &[0xFE, 0xFF, 0xFF, 0xFF]).unwrap(),
+        Value::I32(-2)
+    );
+    assert_eq!(
+        decode_sdo_value(DataType::String, b"EL3102\0\0").unwrap(),
+        Value::String("EL3102".into())
+    );
+    assert_eq!(
+        decode_sdo_value(DataType::Bit3, &[0xFD]).unwrap(),
+        Value::U8(5)
+    );
+    assert_eq!(
+        decode_sdo_value(DataType::U16Array, &[0x41, 0x00, 0x42, 0x00]).unwrap(),
+        Value::U16Array(vec![0x41, 0x42])
+    );
+    assert!(decode_sdo_value(DataType::U32, &[0x01, 0x02]).is_err());
+}
+
+#[test]
+fn test_sdo_value_conversion() {
+    assert_eq!(u32::from_value(Value::U16(7)), Some(7));
+    assert_eq!(u8::from_value(Value::U16(0x100)), None);
+    assert_eq!(i8::from_value(Value::U8(0x80)), None);
+    assert_eq!(300_u32.to_value(DataType::U16), Some(Value::U16(300)));
+    assert_eq!(300_u32.to_value(DataType::U8), None);
+    assert_eq!(true.to_value(DataType::Bit1), Some(Value::Bit1(true)));
+    assert_eq!(u8::from_value(Value::Bit1(true)), Some(1));
+    assert_eq!(5_u8.to_value(DataType::Bit3), Some(Value::U8(5)));
+    assert_eq!(9_u8.to_value(DataType::Bit3), None);
+    assert_eq!(2_u8.to_value(DataType::Bit1), None);
+    assert_eq!(Value::U8(0xFD).to_value(DataType::Bit3), Some(Value::U8(5)));
+    assert_eq!(Value::U8(1).to_value(DataType::U16), None);
+    assert_eq!(encode_sdo_value(&Value::I16(-2)), vec![0xFE, 0xFF]);
+    assert_eq!(
+        Vec::<u8>::from_value(Value::U32(0x0102_0304)),
+        Some(vec![4, 3, 2, 1])
+    );
+}
+
+#[test]
+fn test_decode_complete_access() {
+    let entries = [
+        (SubIdx::from(1), DataType::U16, 16),
+        (SubIdx::from(2), DataType::Bool, 1),
+        (SubIdx::from(3), DataType::Bit3, 3),
+        (SubIdx::from(5), DataType::U8, 4),
+        (SubIdx::from(6), DataType::U32, 32),
+    ];
+    let data = [4, 0, 0x34, 0x12, 0b0111_1011, 0x78, 0x56, 0x34, 0x12];
+    assert_eq!(
+        decode_complete_access(&entries, &data).unwrap(),
+        vec![
+            (SubIdx::from(1), Value::U16(0x1234)),
+            (SubIdx::from(2), Value::Bool(true)),
+            (SubIdx::from(3), Value::U8(5)),
+            (SubIdx::from(5), Value::U8(7)),
+            (SubIdx::from(6), Value::U32(0x1234_5678)),
+        ]
+    );
+    assert!(decode_complete_access(&entries, &data[..8]).is_err());
+}
+
+#[test]
+fn test_sdo_abort_code_display() {
+    assert_eq!(
+        SdoAbortCode(0x0609_0011).to_string(),
+        "0x06090011 (Subindex does not exist)"
+    );
+    assert_eq!(
+        SdoAbortCode(0x1234_5678).to_string(),
+        "0x12345678 (unknown abort code)"
+    );
+}
@@ -0,0 +1,528 @@
+// Part of ethercat-rs. Copyright 2018-2022 by the authors.
+// This work is dual-licensed under Apache 2.0 and MIT terms.
+
+//! Decoding of the Slave Information Interface (SII) EEPROM image.
+
+use crate::types::{Error, MailboxProtocols, Result, SlaveId, SlaveRev};
+use ethercat_types::{Idx, PdoEntryIdx, SmIdx, SmType, SubIdx};
+use std::convert::TryFrom;
+
+/// Number of words in the fixed SII header before the first category.
+const SII_HEADER_WORDS: usize = 0x40;
+
+const CAT_STRINGS: u16 = 10;
+const CAT_GENERAL: u16 = 30;
+const CAT_FMMU: u16 = 40;
+const CAT_SYNCM: u16 = 41;
+const CAT_TXPDO: u16 = 50;
+const CAT_RXPDO: u16 = 51;
+const CAT_DC: u16 = 60;
+const CAT_END: u16 = 0xFFFF;
+
+/// A decoded SII image.
+#[derive(Debug, Clone)]
+pub struct Sii {
+    pub header: SiiHeader,
+    pub categories: Vec<SiiCategory>,
+}
+
+/// The fixed SII header (words 0x0000 to 0x003F).
+#[derive(Debug, Clone)]
+pub struct SiiHeader {
+    pub pdi_control: u16,
+    pub pdi_config: u16,
+    pub sync_impulse_len: u16,
+    pub pdi_config2: u16,
+    pub alias: u16,
+    pub checksum: u8,
+    /// Whether `checksum` matches the CRC calculated over words 0 to 6.
+    pub checksum_valid: bool,
+    pub id: SlaveId,
+    pub rev: SlaveRev,
+    pub boot_rx_mailbox: SiiMailbox,
+    pub boot_tx_mailbox: SiiMailbox,
+    pub std_rx_mailbox: SiiMailbox,
+    pub std_tx_mailbox: SiiMailbox,
+    pub mailbox_protocols: MailboxProtocols,
+    pub size: u16,
+    pub version: u16,
+}
+
+/// Mailbox sync manager location as stored in the SII header.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SiiMailbox {
+    pub offset: u16,
+    pub size: u16,
+}
+
+#[derive(Debug, Clone)]
+pub enum SiiCategory {
+    Strings(Vec<String>),
+    General(SiiGeneral),
+    Fmmu(Vec<SiiFmmuUsage>),
+    SyncM(Vec<SiiSyncManager>),
+    TxPdo(Vec<SiiPdo>),
+    RxPdo(Vec<SiiPdo>),
+    Dc(Vec<SiiDcSync>),
+    /// A category that is not decoded by this crate.
+    Other {
+        category_type: u16,
+        data: Vec<u8>,
+    },
+}
+
+/// Contents of the `General` category.
+///
+/// All `*_idx` fields refer to the `Strings` category, see [`Sii::string`].
+#[derive(Debug, Clone, Default)]
+pub struct SiiGeneral {
+    pub group_idx: u8,
+    pub image_idx: u8,
+    pub order_idx: u8,
+    pub name_idx: u8,
+    pub coe_details: u8,
+    pub foe_details: u8,
+    pub eoe_details: u8,
+    pub soe_channels: u8,
+    pub ds402_channels: u8,
+    pub sysman_class: u8,
+    pub flags: u8,
+    pub current_on_ebus: i16,
+    pub physical_port: u16,
+    pub physical_memory_address: u16,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SiiFmmuUsage {
+    Unused,
+    Outputs,
+    Inputs,
+    SyncMStatus,
+    Unknown(u8),
+}
+
+/// Sync manager description from the `SyncM` category.
+#[derive(Debug, Clone, Copy)]
+pub struct SiiSyncManager {
+    pub start_addr: u16,
+    pub length: u16,
+    pub control_register: u8,
+    pub status_register: u8,
+    pub enable: bool,
+    pub sm_type: Option<SmType>,
+}
+
+/// PDO description from the `TxPDO` or `RxPDO` category.
+#[derive(Debug, Clone)]
+pub struct SiiPdo {
+    pub idx: Idx,
+    pub sm: SmIdx,
+    pub sync: u8,
+    pub name_idx: u8,
+    pub flags: u16,
+    pub entries: Vec<SiiPdoEntry>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct SiiPdoEntry {
+    pub entry_idx: PdoEntryIdx,
+    pub name_idx: u8,
+    pub data_type: u8,
+    pub bit_len: u8,
+    pub flags: u16,
+}
+
+/// Distributed clock sync configuration from the `DC` category.
+#[derive(Debug, Clone, Copy)]
+pub struct SiiDcSync {
+    pub cycle_time0: u32,
+    pub shift_time0: u32,
+    pub shift_time1: u32,
+    pub sync1_cycle_factor: i16,
+    pub assign_activate: u16,
+    pub sync0_cycle_factor: i16,
+    pub name_idx: u8,
+    pub desc_idx: u8,
+}
+
+impl Sii {
+    /// Decode an SII image from the words returned by [`Master::sii_read`](crate::Master::sii_read).
+    pub fn from_words(words: &[u16]) -> Result<Self> {
+        let bytes: Vec<u8> = words.iter().flat_map(|w| w.to_le_bytes()).collect();
+        Self::from_bytes(&bytes)
+    }
+
+    /// Decode an SII image stored as little endian bytes, e.g. an EEPROM dump file.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() < SII_HEADER_WORDS * 2 {
+            return Err(Error::InvalidSii(format!(
+                "image has {} bytes, the header alone needs {}",
+                bytes.len(),
+                SII_HEADER_WORDS * 2
+            )));
+        }
+        let header = parse_header(bytes);
+        let mut categories = vec![];
+        let mut pos = SII_HEADER_WORDS * 2;
+        while pos + 2 <= bytes.len() {
+            let category_type = read_u16(bytes, pos);
+            if category_type == CAT_END {
+                break;
+            }
+            if pos + 4 > bytes.len() {
+                return Err(Error::InvalidSii(format!(
+                    "truncated header of category {} at byte 0x{:X}",
+                    category_type, pos
+                )));
+            }
+            let len = read_u16(bytes, pos + 2) as usize * 2;
+            let data = bytes.get(pos + 4..pos + 4 + len).ok_or_else(|| {
+                Error::InvalidSii(format!(
+                    "category {} at byte 0x{:X} exceeds the image",
+                    category_type, pos
+                ))
+            })?;
+            categories.push(parse_category(category_type, data)?);
+            pos += 4 + len;
+        }
+        Ok(Sii { header, categories })
+    }
+
+    pub fn strings(&self) -> &[String] {
+        self.categories
+            .iter()
+            .find_map(|c| match c {
+                SiiCategory::Strings(s) => Some(s.as_slice()),
+                _ => None,
+            })
+            .unwrap_or(&[])
+    }
+
+    /// Look up a string by its (1-based) index, as used by the other categories.
+    pub fn string(&self, idx: u8) -> Option<&str> {
+        match idx {
+            0 => None,
+            i => self.strings().get(i as usize - 1).map(String::as_str),
+        }
+    }
+
+    pub fn general(&self) -> Option<&SiiGeneral> {
+        self.categories.iter().find_map(|c| match c {
+            SiiCategory::General(g) => Some(g),
+            _ => None,
+        })
+    }
+
+    pub fn sync_managers(&self) -> impl Iterator<Item = &SiiSyncManager> {
+        self.categories
+            .iter()
+            .filter_map(|c| match c {
+                SiiCategory::SyncM(s) => Some(s.iter()),
+                _ => None,
+            })
+            .flatten()
+    }
+
+    pub fn tx_pdos(&self) -> impl Iterator<Item = &SiiPdo> {
+        self.categories
+            .iter()
+            .filter_map(|c| match c {
+                SiiCategory::TxPdo(p) => Some(p.iter()),
+                _ => None,
+            })
+            .flatten()
+    }
+
+    pub fn rx_pdos(&self) -> impl Iterator<Item = &SiiPdo> {
+        self.categories
+            .iter()
+            .filter_map(|c| match c {
+                SiiCategory::RxPdo(p) => Some(p.iter()),
+                _ => None,
+            })
+            .flatten()
+    }
+}
+
+/// Calculate the CRC over the first seven SII words, which is stored
+/// in the low byte of word 0x0007.
+pub fn sii_checksum(words: &[u16]) -> Result<u8> {
+    let header = words
+        .get(..7)
+        .ok_or_else(|| Error::InvalidSii("checksum needs at least 7 words".into()))?;
+    let bytes: Vec<u8> = header.iter().flat_map(|w| w.to_le_bytes()).collect();
+    Ok(crc8(&bytes))
+}
+
+fn crc8(bytes: &[u8]) -> u8 {
+    let mut crc = 0xFF_u8;
+    for byte in bytes {
+        crc ^= *byte;
+        for _ in 0..8 {
+            crc = if crc & 0x80 != 0 {
+                (crc << 1) ^ 0x07
+            } else {
+                crc << 1
+            };
+        }
+    }
+    crc
+}
+
+fn read_u16(data: &[u8], pos: usize) -> u16 {
+    u16::from_le_bytes([data[pos], data[pos + 1]])
+}
+
+fn read_u32(data: &[u8], pos: usize) -> u32 {
+    u32::from_le_bytes([data[pos], data[pos + 1], data[pos + 2], data[pos + 3]])
+}
+
+fn parse_header(b: &[u8]) -> SiiHeader {
+    let mailbox = |word: usize| SiiMailbox {
+        offset: read_u16(b, word * 2),
+        size: read_u16(b, word * 2 + 2),
+    };
+    SiiHeader {
+        pdi_control: read_u16(b, 0x00),
+        pdi_config: read_u16(b, 0x02),
+        sync_impulse_len: read_u16(b, 0x04),
+        pdi_config2: read_u16(b, 0x06),
+        alias: read_u16(b, 0x08),
+        checksum: b[0x0E],
+        checksum_valid: crc8(&b[..0x0E]) == b[0x0E],
+        id: SlaveId {
+            vendor_id: read_u32(b, 0x10),
+            product_code: read_u32(b, 0x14),
+        },
+        rev: SlaveRev {
+            revision_number: read_u32(b, 0x18),
+            serial_number: read_u32(b, 0x1C),
+        },
+        boot_rx_mailbox: mailbox(0x14),
+        boot_tx_mailbox: mailbox(0x16),
+        std_rx_mailbox: mailbox(0x18),
+        std_tx_mailbox: mailbox(0x1A),
+        mailbox_protocols: MailboxProtocols::from_bits_retain(read_u16(b, 0x38)),
+        size: read_u16(b, 0x7C),
+        version: read_u16(b, 0x7E),
+    }
+}
+
+fn parse_category(category_type: u16, data: &[u8]) -> Result<SiiCategory> {
+    let truncated = || Error::InvalidSii(format!("category {} is truncated", category_type));
+    Ok(match category_type {
+        CAT_STRINGS => {
+            let count = *data.first().ok_or_else(truncated)?;
+            let mut strings = Vec::with_capacity(count as usize);
+            let mut pos = 1;
+            for _ in 0..count {
+                let len = *data.get(pos).ok_or_else(truncated)? as usize;
+                let s = data.get(pos + 1..pos + 1 + len).ok_or_else(truncated)?;
+                strings.push(String::from_utf8_lossy(s).into_owned());
+                pos += 1 + len;
+            }
+            SiiCategory::Strings(strings)
+        }
+        CAT_GENERAL => {
+            if data.len() < 20 {
+                return Err(truncated());
+            }
+            SiiCategory::General(SiiGeneral {
+                group_idx: data[0],
+                image_idx: data[1],
+                order_idx: data[2],
+                name_idx: data[3],
+                coe_details: data[5],
+                foe_details: data[6],
+                eoe_details: data[7],
+                soe_channels: data[8],
+                ds402_channels: data[9],
+                sysman_class: data[10],
+                flags: data[11],
+                current_on_ebus: read_u16(data, 12) as i16,
+                physical_port: read_u16(data, 16),
+                physical_memory_address: read_u16(data, 18),
+            })
+        }
+        CAT_FMMU => SiiCategory::Fmmu(
+            data.iter()
+                .map(|u| match u {
+                    0x00 | 0xFF => SiiFmmuUsage::Unused,
+                    0x01 => SiiFmmuUsage::Outputs,
+                    0x02 => SiiFmmuUsage::Inputs,
+                    0x03 => SiiFmmuUsage::SyncMStatus,
+                    x => SiiFmmuUsage::Unknown(*x),
+                })
+                .collect(),
+        ),
+        CAT_SYNCM => SiiCategory::SyncM(
+            data.chunks_exact(8)
+                .map(|d| SiiSyncManager {
+                    start_addr: read_u16(d, 0),
+                    length: read_u16(d, 2),
+                    control_register: d[4],
+                    status_register: d[5],
+                    enable: d[6] & 0x01 != 0,
+                    sm_type: SmType::try_from(d[7]).ok(),
+                })
+                .collect(),
+        ),
+        CAT_TXPDO | CAT_RXPDO => {
+            let mut pdos = vec![];
+            let mut pos = 0;
+            while pos + 8 <= data.len() {
+                let d = &data[pos..pos + 8];
+                let entry_count = d[2] as usize;
+                let entries = data
+                    .get(pos + 8..pos + 8 + entry_count * 8)
+                    .ok_or_else(truncated)?
+                    .chunks_exact(8)
+                    .map(|e| SiiPdoEntry {
+                        entry_idx: PdoEntryIdx {
+                            idx: Idx::from(read_u16(e, 0)),
+                            sub_idx: SubIdx::from(e[2]),
+                        },
+                        name_idx: e[3],
+                        data_type: e[4],
+                        bit_len: e[5],
+                        flags: read_u16(e, 6),
+                    })
+                    .collect();
+                pdos.push(SiiPdo {
+                    idx: Idx::from(read_u16(d, 0)),
+                    sm: SmIdx::from(d[3]),
+                    sync: d[4],
+                    name_idx: d[5],
+                    flags: read_u16(d, 6),
+                    entries,
+                });
+                pos += 8 + entry_count * 8;
+            }
+            if category_type == CAT_TXPDO {
+                SiiCategory::TxPdo(pdos)
+            } else {
+                SiiCategory::RxPdo(pdos)
+            }
+        }
+        CAT_DC => SiiCategory::Dc(
+            data.chunks_exact(24)
+                .map(|d| SiiDcSync {
+                    cycle_time0: read_u32(d, 0),
+                    shift_time0: read_u32(d, 4),
+                    shift_time1: read_u32(d, 8),
+                    sync1_cycle_factor: read_u16(d, 12) as i16,
+                    assign_activate: read_u16(d, 14),
+                    sync0_cycle_factor: read_u16(d, 16) as i16,
+                    name_idx: d[18],
+                    desc_idx: d[19],
+                })
+                .collect(),
+        ),
+        _ => SiiCategory::Other {
+            category_type,
+            data: data.to_vec(),
+        },
+    })
+}
+
+#[cfg(test)]
+fn test_image() -> Vec<u16> {
+    let mut words = vec![0_u16; SII_HEADER_WORDS];
+    words[0x04] = 0x1234; // alias
+    words[0x08] = 0x0002; // vendor id
+    words[0x0A] = 0x1A24; // product code
+    words[0x0B] = 0x0C1E;
+    words[0x18] = 0x1000; // std rx mailbox
+    words[0x19] = 0x0080;
+    words[0x1C] = 0x000C; // CoE + FoE
+    words[0x07] = sii_checksum(&words).unwrap() as u16;
+    // Strings: "EL6692", "Bridge"
+    words.extend_from_slice(&[CAT_STRINGS, 8]);
+    let strings = b"\x02\x06EL6692\x06Bridge\x00";
+    words.extend(strings.chunks(2).map(|c| u16::from_le_bytes([c[0], c[1]])));
+    // General with name index 2 and current -100 mA
+    words.extend_from_slice(&[CAT_GENERAL, 16, 0x0201, 0x0200, 0, 0, 0, 0, 0xFF9C]);
+    words.extend_from_slice(&[0; 9]);
+    // SyncM: one output sync manager
+    words.extend_from_slice(&[CAT_SYNCM, 4, 0x1100, 0x0002, 0x0064, 0x0301]);
+    // RxPDO 0x1600 on SM2 with one 16 bit entry 0x7000:01
+    words.extend_from_slice(&[CAT_RXPDO, 8, 0x1600, 0x0201, 0x0300, 0x0000]);
+    words.extend_from_slice(&[0x7000, 0x0001, 0x1006, 0x0000]);
+    words.push(CAT_END);
+    words
+}
+
+#[test]
+fn test_sii_from_words() {
+    let words = test_image();
+    let sii = Sii::from_words(&words).unwrap();
+    assert!(sii.header.checksum_valid);
+    assert_eq!(sii.header.alias, 0x1234);
+    assert_eq!(sii.header.id.vendor_id, 2);
+    assert_eq!(sii.header.id.product_code, 0x0C1E_1A24);
+    assert_eq!(
+        sii.header.std_rx_mailbox,
+        SiiMailbox {
+            offset: 0x1000,
+            size: 0x80
+        }
+    );
+    assert_eq!(
+        sii.header.mailbox_protocols,
+        MailboxProtocols::COE | MailboxProtocols::FOE
+    );
+    assert_eq!(sii.strings(), ["EL6692", "Bridge"]);
+    let general = sii.general().unwrap();
+    assert_eq!(sii.string(general.name_idx), Some("Bridge"));
+    assert_eq!(sii.string(0), None);
+    assert_eq!(general.current_on_ebus, -100);
+    let sm = sii.sync_managers().next().unwrap();
+    assert_eq!(sm.start_addr, 0x1100);
+    assert_eq!(sm.sm_type, Some(SmType::Outputs));
+    assert!(sm.enable);
+    let pdo = sii.rx_pdos().next().unwrap();
+    assert_eq!(u16::from(pdo.idx), 0x1600);
+    assert_eq!(u8::from(pdo.sm), 2);
+    assert_eq!(pdo.entries.len(), 1);
+    assert_eq!(u16::from(pdo.entries[0].entry_idx.idx), 0x7000);
+    assert_eq!(pdo.entries[0].bit_len, 16);
+    assert_eq!(sii.tx_pdos().count(), 0);
+}
+
+#[test]
+fn test_sii_truncated() {
+    let mut words = test_image();
+    assert!(Sii::from_words(&words[..0x20]).is_err());
+    // cut the image in the middle of the RxPDO category
+    words.truncate(words.len() - 3);
+    assert!(Sii::from_words(&words).is_err());
+}
+
+#[test]
+fn test_sii_checksum() {
+    // Reference implementation from the IgH `ethercat sii_write` command.
+    fn calc_sii_crc(data: &[u8]) -> u8 {
+        let mut crc = 0x48_u8;
+        for byte in data {
+            for i in 0..8 {
+                let bit = crc & 0x80;
+                crc = (crc << 1) | ((byte >> (7 - i)) & 0x01);
+                if bit != 0 {
+                    crc ^= 0x07;
+                }
+            }
+        }
+        for _ in 0..8 {
+            let bit = crc & 0x80;
+            crc <<= 1;
+            if bit != 0 {
+                crc ^= 0x07;
+            }
+        }
+        crc
+    }
+    let words = [0x0C08, 0x6E00, 0x0000, 0x0000, 0x0000, 0x0000, 0x0000];
+    let bytes: Vec<u8> = words.iter().flat_map(|w: &u16| w.to_le_bytes()).collect();
+    assert_eq!(sii_checksum(&words).unwrap(), calc_sii_crc(&bytes));
+    assert!(sii_checksum(&words[..6]).is_err());
+}
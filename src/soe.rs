@@ -0,0 +1,197 @@
+// Part of ethercat-rs. Copyright 2018-2022 by the authors.
+// This work is dual-licensed under Apache 2.0 and MIT terms.
+
+//! Servo drive profile over EtherCAT (SoE) identification numbers and error codes.
+
+use std::{fmt, str::FromStr};
+
+/// SoE identification number (IDN).
+///
+/// Displayed in the usual `S-0-0001` (standard) or `P-0-0001`
+/// (product specific) notation.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Idn(u16);
+
+impl Idn {
+    pub const fn new(idn: u16) -> Self {
+        Self(idn)
+    }
+
+    /// Build an IDN from its parts; `set` must be below 8 and `block` below 4096.
+    pub const fn from_parts(product_specific: bool, set: u8, block: u16) -> Self {
+        Self(((product_specific as u16) << 15) | ((set as u16 & 0x7) << 12) | (block & 0x0FFF))
+    }
+
+    pub const fn is_product_specific(self) -> bool {
+        self.0 & 0x8000 != 0
+    }
+
+    pub const fn parameter_set(self) -> u8 {
+        ((self.0 >> 12) & 0x7) as u8
+    }
+
+    pub const fn data_block(self) -> u16 {
+        self.0 & 0x0FFF
+    }
+}
+
+impl From<u16> for Idn {
+    fn from(idn: u16) -> Self {
+        Self(idn)
+    }
+}
+
+impl From<Idn> for u16 {
+    fn from(idn: Idn) -> Self {
+        idn.0
+    }
+}
+
+impl fmt::Display for Idn {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}-{}-{:04}",
+            if self.is_product_specific() { 'P' } else { 'S' },
+            self.parameter_set(),
+            self.data_block()
+        )
+    }
+}
+
+impl fmt::Debug for Idn {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Idn({})", self)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseIdnError(String);
+
+impl fmt::Display for ParseIdnError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid IDN '{}'", self.0)
+    }
+}
+
+impl std::error::Error for ParseIdnError {}
+
+impl FromStr for Idn {
+    type Err = ParseIdnError;
+
+    /// Parse an IDN in `S-0-0001` / `P-0-0001` notation.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || ParseIdnError(s.to_owned());
+        let mut parts = s.split('-');
+        let product_specific = match parts.next() {
+            Some("S") | Some("s") => false,
+            Some("P") | Some("p") => true,
+            _ => return Err(err()),
+        };
+        let set: u8 = parts.next().and_then(|p| p.parse().ok()).ok_or_else(err)?;
+        let block: u16 = parts.next().and_then(|p| p.parse().ok()).ok_or_else(err)?;
+        if parts.next().is_some() || set > 7 || block > 4095 {
+            return Err(err());
+        }
+        Ok(Self::from_parts(product_specific, set, block))
+    }
+}
+
+/// SoE error code as returned by a drive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SoeErrorCode(pub u16);
+
+impl SoeErrorCode {
+    /// Human-readable description of a known error code.
+    pub fn description(self) -> Option<&'static str> {
+        Some(match self.0 {
+            0x1001 => "No IDN",
+            0x1009 => "Invalid access to element 1",
+            0x2001 => "No name",
+            0x2002 => "Name transmission too short",
+            0x2003 => "Name transmission too long",
+            0x2004 => "Name cannot be changed, read only",
+            0x2005 => "Name is write-protected at this time",
+            0x3002 => "Attribute transmission too short",
+            0x3003 => "Attribute transmission too long",
+            0x3004 => "Attribute cannot be changed, read only",
+            0x3005 => "Attribute is write-protected at this time",
+            0x4001 => "No unit",
+            0x4002 => "Unit transmission too short",
+            0x4003 => "Unit transmission too long",
+            0x4004 => "Unit cannot be changed, read only",
+            0x4005 => "Unit is write-protected at this time",
+            0x5001 => "No minimum input value",
+            0x5002 => "Minimum input value transmission too short",
+            0x5003 => "Minimum input value transmission too long",
+            0x5004 => "Minimum input value cannot be changed, read only",
+            0x5005 => "Minimum input value is write-protected at this time",
+            0x6001 => "No maximum input value",
+            0x6002 => "Maximum input value transmission too short",
+            0x6003 => "Maximum input value transmission too long",
+            0x6004 => "Maximum input value cannot be changed, read only",
+            0x6005 => "Maximum input value is write-protected at this time",
+            0x7002 => "Operation data transmission too short",
+            0x7003 => "Operation data transmission too long",
+            0x7004 => "Operation data cannot be changed, read only",
+            0x7005 => "Operation data is write-protected at this time (state)",
+            0x7006 => "Operation data is smaller than the minimum input value",
+            0x7007 => "Operation data is greater than the maximum input value",
+            0x7008 => "Invalid operation data: configured IDN will not be supported",
+            0x7009 => "Operation data write protected by a password",
+            0x700A => "Operation data is write protected, it is configured cyclically",
+            0x700B => "Invalid indirect addressing",
+            0x700C => "Operation data is write protected due to other settings",
+            0x7010 => "Procedure command already active",
+            0x7011 => "Procedure command not interruptible",
+            0x7012 => "Procedure command not executable at this time (state)",
+            0x7013 => "Procedure command not executable (invalid or false parameters)",
+            0x7014 => "No data state",
+            0x8001 => "No default value",
+            0x8002 => "Default value transmission too long",
+            0x8004 => "Default value cannot be changed, read only",
+            0x800A => "Invalid drive number",
+            0x800B => "General error",
+            0x800C => "No element addressed",
+            _ => return None,
+        })
+    }
+}
+
+impl fmt::Display for SoeErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.description() {
+            Some(d) => write!(f, "0x{:04X} ({})", self.0, d),
+            None => write!(f, "0x{:04X} (unknown error code)", self.0),
+        }
+    }
+}
+
+#[test]
+fn test_idn_display() {
+    assert_eq!(Idn::new(1).to_string(), "S-0-0001");
+    assert_eq!(Idn::new(0x8000 | 100).to_string(), "P-0-0100");
+    assert_eq!(Idn::new(0x7000 | 4095).to_string(), "S-7-4095");
+    assert_eq!(format!("{:?}", Idn::new(0x8005)), "Idn(P-0-0005)");
+}
+
+#[test]
+fn test_idn_from_str() {
+    assert_eq!("S-0-0001".parse(), Ok(Idn::new(1)));
+    assert_eq!("P-1-0032".parse(), Ok(Idn::from_parts(true, 1, 32)));
+    assert_eq!("P-1-0032".parse::<Idn>().unwrap(), Idn::new(0x9020));
+    assert!("X-0-0001".parse::<Idn>().is_err());
+    assert!("S-8-0001".parse::<Idn>().is_err());
+    assert!("S-0-4096".parse::<Idn>().is_err());
+    assert!("S-0".parse::<Idn>().is_err());
+    assert!("S-0-1-2".parse::<Idn>().is_err());
+}
+
+#[test]
+fn test_soe_error_code_display() {
+    assert_eq!(SoeErrorCode(0x1001).to_string(), "0x1001 (No IDN)");
+    assert_eq!(
+        SoeErrorCode(0x1234).to_string(),
+        "0x1234 (unknown error code)"
+    );
+}
@@ -1,9 +1,16 @@
 // Part of ethercat-rs. Copyright 2018-2022 by the authors.
 // This work is dual-licensed under Apache 2.0 and MIT terms.
 
-use crate::ec;
+use crate::{
+    ec,
+    foe::{FoeErrorCode, FoeResult},
+    sdo::SdoAbortCode,
+    sii::SiiMailbox,
+    soe::{Idn, SoeErrorCode},
+};
+use bitflags::bitflags;
 use derive_new::new;
-use std::io;
+use std::{fmt, io, net::Ipv4Addr};
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -24,6 +31,35 @@ pub enum Error {
     InvalidAlState(u8),
     #[error("SDO/VoE/register request failed")]
     RequestFailed,
+    #[error("Invalid SII data: {0}")]
+    InvalidSii(String),
+    #[error(
+        "SDO abort {code} on slave {} accessing {:X}:{}",
+        u16::from(*.slave),
+        u16::from(.idx.idx),
+        u8::from(.idx.sub_idx)
+    )]
+    SdoAbort {
+        slave: SlavePos,
+        idx: SdoIdx,
+        code: SdoAbortCode,
+    },
+    #[error("FoE error {result:?} (code {code}) on slave {}", u16::from(*.slave))]
+    Foe {
+        slave: SlavePos,
+        result: FoeResult,
+        code: FoeErrorCode,
+    },
+    #[error("SoE error {code} on slave {} accessing {idn}", u16::from(*.slave))]
+    Soe {
+        slave: SlavePos,
+        idn: Idn,
+        code: SoeErrorCode,
+    },
+    #[error("Invalid SDO data: {0}")]
+    InvalidSdoData(String),
+    #[error("Invalid AL state transition {from:?} -> {to:?}")]
+    InvalidAlStateTransition { from: AlState, to: AlState },
     #[error(transparent)]
     Io(#[from] io::Error),
 }
@@ -81,9 +117,132 @@ impl SlaveAddr {
 #[derive(Debug, Clone)]
 pub struct MasterInfo {
     pub slave_count: u32,
+    pub config_count: u32,
+    pub domain_count: u32,
+    pub eoe_handler_count: u32,
+    pub phase: MasterPhase,
+    pub active: bool,
+    /// Link state of the main device
     pub link_up: bool,
     pub scan_busy: bool,
+    /// The main device, followed by the backup device if redundancy is used
+    pub devices: Vec<DeviceInfo>,
+    pub tx_count: u64,
+    pub rx_count: u64,
+    pub tx_bytes: u64,
+    pub rx_bytes: u64,
+    /// Frames/s
+    pub tx_frame_rate: Rate,
+    /// Frames/s
+    pub rx_frame_rate: Rate,
+    /// Bytes/s
+    pub tx_byte_rate: Rate,
+    /// Bytes/s
+    pub rx_byte_rate: Rate,
+    /// Lost frames/s
+    pub loss_rate: Rate,
     pub app_time: u64,
+    pub dc_ref_time: u64,
+    /// Position of the DC reference clock, if there is one
+    pub ref_clock: Option<SlavePos>,
+    /// Size of the frame capture buffer in bytes
+    #[cfg(feature = "sncn")]
+    pub pcap_size: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MasterPhase {
+    /// Waiting for devices
+    Orphaned,
+    Idle,
+    Operation,
+}
+
+/// Statistics of an Ethernet device used by the master.
+#[derive(Debug, Clone)]
+pub struct DeviceInfo {
+    pub address: MacAddr,
+    pub attached: bool,
+    pub link_up: bool,
+    pub tx_count: u64,
+    pub rx_count: u64,
+    pub tx_bytes: u64,
+    pub rx_bytes: u64,
+    pub tx_errors: u64,
+    /// Frames/s
+    pub tx_frame_rate: Rate,
+    /// Frames/s
+    pub rx_frame_rate: Rate,
+    /// Bytes/s
+    pub tx_byte_rate: Rate,
+    /// Bytes/s
+    pub rx_byte_rate: Rate,
+}
+
+/// A rate, averaged over different periods.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Rate {
+    pub avg_1s: f64,
+    pub avg_10s: f64,
+    pub avg_60s: f64,
+}
+
+impl Rate {
+    pub(crate) fn from_raw(rates: [i32; 3], scale: f64) -> Self {
+        Self {
+            avg_1s: rates[0] as f64 / scale,
+            avg_10s: rates[1] as f64 / scale,
+            avg_60s: rates[2] as f64 / scale,
+        }
+    }
+}
+
+/// An Ethernet MAC address.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MacAddr(pub [u8; 6]);
+
+impl From<[u8; 6]> for MacAddr {
+    fn from(addr: [u8; 6]) -> Self {
+        Self(addr)
+    }
+}
+
+impl fmt::Display for MacAddr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let a = self.0;
+        write!(
+            f,
+            "{:02x}:{:02x}:{:02x}:{:02x}:{:02x}:{:02x}",
+            a[0], a[1], a[2], a[3], a[4], a[5]
+        )
+    }
+}
+
+/// Statistics of an Ethernet-over-EtherCAT (EoE) handler.
+#[derive(Debug, Clone)]
+pub struct EoeHandlerInfo {
+    pub name: String,
+    pub slave_pos: SlavePos,
+    pub open: bool,
+    pub rx_bytes: u32,
+    /// Receive rate in bytes/s
+    pub rx_rate: u32,
+    pub tx_bytes: u32,
+    /// Transmit rate in bytes/s
+    pub tx_rate: u32,
+    pub tx_queued_frames: u32,
+    pub tx_queue_size: u32,
+}
+
+/// IP parameters of an EoE slave; only the given parameters are set.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct EoeIpParams {
+    pub mac_address: Option<MacAddr>,
+    pub ip_address: Option<Ipv4Addr>,
+    pub subnet_mask: Option<Ipv4Addr>,
+    pub gateway: Option<Ipv4Addr>,
+    pub dns: Option<Ipv4Addr>,
+    pub name: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -101,8 +260,113 @@ pub struct ConfigInfo {
     pub slave_position: Option<SlavePos>,
     pub sdo_count: u32,
     pub idn_count: u32,
-    // TODO: more attributes are returned:
-    // syncs[*], watchdog_*, dc_*
+    #[cfg(not(feature = "sncn"))]
+    pub flag_count: u32,
+    /// Sync managers that are configured or have PDOs assigned
+    pub syncs: Vec<ConfigSmInfo>,
+    pub watchdog_divider: u16,
+    pub watchdog_intervals: u16,
+    pub dc_assign_activate: u16,
+    pub dc_sync: [DcSyncSignal; 2],
+}
+
+/// Sync manager of a slave config
+#[derive(Debug, Clone, Copy)]
+pub struct ConfigSmInfo {
+    pub idx: SmIdx,
+    /// Whether direction and watchdog mode were configured explicitly
+    pub configured: bool,
+    pub direction: SyncDirection,
+    pub watchdog_mode: WatchdogMode,
+    pub pdo_count: u32,
+}
+
+/// DC sync signal of a slave config (times in ns)
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct DcSyncSignal {
+    pub cycle_time: u32,
+    pub shift_time: i32,
+}
+
+/// PDO assigned in a slave config, including its mapping
+#[derive(Debug, Clone)]
+pub struct ConfigPdoInfo {
+    pub sm: SmIdx,
+    pub pos: PdoPos,
+    pub idx: Idx,
+    pub name: String,
+    pub entries: Vec<PdoEntryInfo>,
+}
+
+/// SDO configured in a slave config
+#[derive(Debug, Clone)]
+pub struct ConfigSdoInfo {
+    pub sdo_idx: SdoIdx,
+    pub complete_access: bool,
+    /// Size of the stored data; if it is larger than `data`, the master
+    /// only reported the first `EC_MAX_SDO_DATA_SIZE` bytes
+    pub size: usize,
+    pub data: Vec<u8>,
+}
+
+/// IDN configured in a slave config
+#[derive(Debug, Clone)]
+pub struct ConfigIdnInfo {
+    pub drive_no: u8,
+    pub idn: Idn,
+    pub al_state: AlState,
+    pub data: Vec<u8>,
+}
+
+/// Feature flag set in a slave config
+#[cfg(not(feature = "sncn"))]
+#[derive(Debug, Clone)]
+pub struct ConfigFlagInfo {
+    pub key: SlaveConfigFlag,
+    pub value: i32,
+}
+
+/// Key of a slave config feature flag.
+///
+/// Flags are evaluated by the master while configuring the slave; flags
+/// unknown to the master are stored, but have no effect.
+#[cfg(not(feature = "sncn"))]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum SlaveConfigFlag {
+    /// Hand over SII access to the PDI after configuration (value != 0)
+    AssignToPdi,
+    /// Delay in ms before requesting SAFEOP
+    WaitBeforeSafeOpMs,
+    Custom(String),
+}
+
+#[cfg(not(feature = "sncn"))]
+impl SlaveConfigFlag {
+    pub fn key(&self) -> &str {
+        match self {
+            SlaveConfigFlag::AssignToPdi => "AssignToPdi",
+            SlaveConfigFlag::WaitBeforeSafeOpMs => "WaitBeforeSAFEOPms",
+            SlaveConfigFlag::Custom(key) => key,
+        }
+    }
+}
+
+#[cfg(not(feature = "sncn"))]
+impl From<&str> for SlaveConfigFlag {
+    fn from(key: &str) -> Self {
+        match key {
+            "AssignToPdi" => SlaveConfigFlag::AssignToPdi,
+            "WaitBeforeSAFEOPms" => SlaveConfigFlag::WaitBeforeSafeOpMs,
+            key => SlaveConfigFlag::Custom(key.to_owned()),
+        }
+    }
+}
+
+#[cfg(not(feature = "sncn"))]
+impl fmt::Display for SlaveConfigFlag {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.key())
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -118,6 +382,76 @@ pub struct SlaveInfo {
     pub sync_count: u8,
     pub sdo_count: u16,
     pub ports: [SlavePortInfo; ec::EC_MAX_PORTS as usize],
+    pub device_index: u32,
+    pub boot_rx_mailbox: SiiMailbox,
+    pub boot_tx_mailbox: SiiMailbox,
+    pub std_rx_mailbox: SiiMailbox,
+    pub std_tx_mailbox: SiiMailbox,
+    pub mailbox_protocols: MailboxProtocols,
+    pub has_general_category: bool,
+    pub coe_details: CoeDetails,
+    pub general_flags: GeneralFlags,
+    pub fmmu_bit: bool,
+    pub dc_supported: bool,
+    pub dc_range: DcRange,
+    pub has_dc_system_time: bool,
+    /// Propagation delay from the reference clock in ns
+    pub transmission_delay: u32,
+    pub sii_nwords: u32,
+    pub group: String,
+    pub image: String,
+    pub order: String,
+}
+
+impl SlaveInfo {
+    /// Whether the slave can be used as DC reference clock.
+    pub const fn supports_dc_reference_clock(&self) -> bool {
+        self.dc_supported && self.has_dc_system_time
+    }
+}
+
+bitflags! {
+    /// Mailbox protocols supported by a slave.
+    #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct MailboxProtocols: u16 {
+        /// ADS over EtherCAT
+        const AOE = 0x01;
+        /// Ethernet over EtherCAT
+        const EOE = 0x02;
+        /// CANopen over EtherCAT
+        const COE = 0x04;
+        /// File access over EtherCAT
+        const FOE = 0x08;
+        /// Servo profile over EtherCAT
+        const SOE = 0x10;
+        /// Vendor specific protocol over EtherCAT
+        const VOE = 0x20;
+    }
+}
+
+/// CoE details from the SII `General` category.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct CoeDetails {
+    pub enable_sdo: bool,
+    pub enable_sdo_info: bool,
+    pub enable_pdo_assign: bool,
+    pub enable_pdo_configuration: bool,
+    pub enable_upload_at_startup: bool,
+    pub enable_sdo_complete_access: bool,
+}
+
+/// General flags from the SII `General` category.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct GeneralFlags {
+    pub enable_safeop: bool,
+    pub enable_not_lrw: bool,
+}
+
+/// Width of the distributed clock system time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DcRange {
+    Bits32,
+    Bits64,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -151,6 +485,30 @@ pub struct SlavePortInfo {
     pub delay_to_next_dc: u32,
 }
 
+/// ESC error counters of a single port.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct PortErrorCounters {
+    pub invalid_frame: u8,
+    pub rx_error: u8,
+    pub forwarded_rx_error: u8,
+    pub lost_link: u8,
+}
+
+/// ESC error counters (registers 0x0300 to 0x0313).
+#[derive(Debug, Clone, Copy)]
+pub struct EscErrorCounters {
+    pub ports: [PortErrorCounters; ec::EC_MAX_PORTS as usize],
+    pub processing_unit: u8,
+    pub pdi: u8,
+}
+
+/// Contents of the AL status register (0x0130).
+#[derive(Debug, Clone, Copy)]
+pub struct AlStatus {
+    pub state: AlState,
+    pub error: bool,
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct SlaveConfigState {
     pub online: bool,
@@ -158,14 +516,14 @@ pub struct SlaveConfigState {
     pub al_state: AlState,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SyncDirection {
     Invalid,
     Output,
     Input,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum WatchdogMode {
     Default,
     Enable,
@@ -260,6 +618,30 @@ pub struct DomainState {
     pub redundancy_active: bool,
 }
 
+/// Size, address and working counters of a domain, as reported by the master
+#[derive(Debug, Clone, Copy)]
+pub struct DomainInfo {
+    pub data_size: usize,
+    pub logical_base_address: u32,
+    /// Working counter of the last cycle, summed over all devices when
+    /// using redundancy
+    pub working_counter: u16,
+    /// Working counter expected if all slaves processed their datagrams
+    pub expected_working_counter: u16,
+    pub fmmu_count: u32,
+}
+
+/// FMMU of a domain, as mapped for a slave config
+#[derive(Debug, Clone, Copy)]
+pub struct DomainFmmuInfo {
+    pub config_alias: u16,
+    pub config_position: u16,
+    pub sync_idx: SmIdx,
+    pub direction: SyncDirection,
+    pub logical_address: u32,
+    pub data_size: usize,
+}
+
 #[derive(Debug, Clone, Copy)]
 pub enum WcState {
     Zero = 0,
@@ -267,6 +649,22 @@ pub enum WcState {
     Complete,
 }
 
+/// State of a non-blocking request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RequestState {
+    Unused,
+    Busy,
+    Success,
+    Error,
+}
+
+/// Header of a vendor specific mailbox (VoE) message.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, new)]
+pub struct VoeHeader {
+    pub vendor_id: u32,
+    pub vendor_type: u16,
+}
+
 pub(crate) fn get_sdo_entry_access(read: [u8; 3], write: [u8; 3]) -> SdoEntryAccess {
     SdoEntryAccess {
         pre_op: access(read[0], write[0]),
@@ -284,6 +682,28 @@ fn access(read: u8, write: u8) -> Access {
     }
 }
 
+impl From<u32> for SyncDirection {
+    fn from(dir: u32) -> Self {
+        match dir {
+            ec::EC_DIR_INVALID => SyncDirection::Invalid,
+            ec::EC_DIR_OUTPUT => SyncDirection::Output,
+            ec::EC_DIR_INPUT => SyncDirection::Input,
+            x => panic!("invalid direction {}", x),
+        }
+    }
+}
+
+impl From<u32> for WatchdogMode {
+    fn from(mode: u32) -> Self {
+        match mode {
+            ec::EC_WD_DEFAULT => WatchdogMode::Default,
+            ec::EC_WD_ENABLE => WatchdogMode::Enable,
+            ec::EC_WD_DISABLE => WatchdogMode::Disable,
+            x => panic!("invalid watchdog mode {}", x),
+        }
+    }
+}
+
 impl From<u32> for WcState {
     fn from(st: u32) -> Self {
         match st {
@@ -294,3 +714,91 @@ impl From<u32> for WcState {
         }
     }
 }
+
+impl From<u32> for RequestState {
+    fn from(st: u32) -> Self {
+        match st {
+            ec::EC_REQUEST_UNUSED => RequestState::Unused,
+            ec::EC_REQUEST_BUSY => RequestState::Busy,
+            ec::EC_REQUEST_SUCCESS => RequestState::Success,
+            ec::EC_REQUEST_ERROR => RequestState::Error,
+            x => panic!("invalid request state {}", x),
+        }
+    }
+}
+
+impl From<u32> for DcRange {
+    fn from(range: u32) -> Self {
+        match range {
+            ec::EC_DC_32 => DcRange::Bits32,
+            ec::EC_DC_64 => DcRange::Bits64,
+            x => panic!("invalid DC range {}", x),
+        }
+    }
+}
+
+impl From<u8> for MasterPhase {
+    fn from(phase: u8) -> Self {
+        match phase {
+            0 => MasterPhase::Orphaned,
+            1 => MasterPhase::Idle,
+            2 => MasterPhase::Operation,
+            x => panic!("invalid master phase {}", x),
+        }
+    }
+}
+
+/// Whether `from` -> `to` is a transition of the EtherCAT state machine that
+/// the master can be configured for.
+///
+/// BOOT is not a state of the master's `ec_al_state_t`, so transitions from
+/// and to BOOT are rejected.
+#[cfg_attr(feature = "sncn", allow(dead_code))]
+pub(crate) const fn is_valid_al_transition(from: AlState, to: AlState) -> bool {
+    use AlState::*;
+    matches!(
+        (from, to),
+        (Init, PreOp)
+            | (PreOp, SafeOp)
+            | (PreOp, Init)
+            | (SafeOp, Op)
+            | (SafeOp, PreOp)
+            | (SafeOp, Init)
+            | (Op, SafeOp)
+            | (Op, PreOp)
+            | (Op, Init)
+    )
+}
+
+#[test]
+fn test_mac_addr_display() {
+    let mac = MacAddr([0x00, 0x1b, 0x21, 0xAB, 0x0c, 0xff]);
+    assert_eq!(mac.to_string(), "00:1b:21:ab:0c:ff");
+}
+
+#[test]
+fn test_al_transitions() {
+    assert!(is_valid_al_transition(AlState::PreOp, AlState::SafeOp));
+    assert!(is_valid_al_transition(AlState::Op, AlState::Init));
+    assert!(!is_valid_al_transition(AlState::Init, AlState::Boot));
+    assert!(!is_valid_al_transition(AlState::Boot, AlState::Init));
+    assert!(!is_valid_al_transition(AlState::Init, AlState::Op));
+    assert!(!is_valid_al_transition(AlState::Boot, AlState::PreOp));
+    assert!(!is_valid_al_transition(AlState::SafeOp, AlState::SafeOp));
+}
+
+#[cfg(not(feature = "sncn"))]
+#[test]
+fn test_slave_config_flag() {
+    assert_eq!(
+        SlaveConfigFlag::from("AssignToPdi"),
+        SlaveConfigFlag::AssignToPdi
+    );
+    assert_eq!(
+        SlaveConfigFlag::WaitBeforeSafeOpMs.key(),
+        "WaitBeforeSAFEOPms"
+    );
+    let custom = SlaveConfigFlag::from("MyQuirk");
+    assert_eq!(custom, SlaveConfigFlag::Custom("MyQuirk".into()));
+    assert_eq!(custom.to_string(), "MyQuirk");
+}